@@ -0,0 +1,256 @@
+//! A unified `{code, msg, data}` envelope for API responses.
+//!
+//! ```json
+//! {"code": 0, "msg": "ok", "data": {"id": 7}}
+//! {"code": 100010001, "msg": "order 7 not found", "error": {...}}
+//! ```
+//!
+//! Errors are projected with [`WidError::to_public`] first. The member names,
+//! the success code, whether `data` appears on errors and the public policy
+//! are set by an [`Envelope`]:
+//!
+//! ```
+//! use widerror::api::{ApiResponse, Envelope};
+//!
+//! struct Legacy;
+//!
+//! impl Envelope for Legacy {
+//!     const CODE: &'static str = "errno";
+//!     const SUCCESS_CODE: i64 = 200;
+//!     const DATA_ON_ERROR: bool = true;
+//! }
+//!
+//! let body = serde_json::to_string(&ApiResponse::<u32, Legacy>::ok(7)).unwrap();
+//! assert_eq!(body, r#"{"errno":200,"msg":"ok","data":7}"#);
+//! ```
+
+// `Result<T, WidError>` is the point of this module.
+#![allow(clippy::result_large_err)]
+
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{ErrorCode, Message, PublicPolicy, WidError};
+
+/// The shape of the envelope.
+pub trait Envelope {
+    const CODE: &'static str = "code";
+    const MESSAGE: &'static str = "msg";
+    const DATA: &'static str = "data";
+    /// Member holding the serialized `WidError`, if any.
+    const ERROR: Option<&'static str> = Some("error");
+    const SUCCESS_CODE: i64 = 0;
+    const SUCCESS_MESSAGE: &'static str = "ok";
+    /// Whether errors carry `"data": null`.
+    const DATA_ON_ERROR: bool = false;
+
+    /// Projects errors before they are written, so that `msg` and `ERROR`
+    /// only show what [`WidError::to_public`] lets through. `None` writes
+    /// them as they are, for callers that may see internal errors.
+    fn public_policy() -> Option<PublicPolicy> {
+        Some(PublicPolicy::default())
+    }
+}
+
+/// `{"code", "msg", "data", "error"}` with success code 0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultEnvelope;
+
+impl Envelope for DefaultEnvelope {}
+
+/// A `Result<T, WidError>` serialized in the envelope `E`.
+pub struct ApiResponse<T, E = DefaultEnvelope> {
+    pub result: Result<T, WidError>,
+    envelope: PhantomData<E>,
+}
+
+impl<T, E> ApiResponse<T, E> {
+    pub fn ok(data: T) -> Self {
+        Ok(data).into()
+    }
+
+    pub fn err(err: WidError) -> Self {
+        Err(err).into()
+    }
+
+    pub fn into_result(self) -> Result<T, WidError> {
+        self.result
+    }
+}
+
+impl<T, E> From<Result<T, WidError>> for ApiResponse<T, E> {
+    fn from(result: Result<T, WidError>) -> Self {
+        ApiResponse {
+            result,
+            envelope: PhantomData,
+        }
+    }
+}
+
+impl<T, E> From<ApiResponse<T, E>> for Result<T, WidError> {
+    fn from(response: ApiResponse<T, E>) -> Self {
+        response.result
+    }
+}
+
+impl<T: Clone, E> Clone for ApiResponse<T, E> {
+    fn clone(&self) -> Self {
+        self.result.clone().into()
+    }
+}
+
+impl<T: Debug, E> Debug for ApiResponse<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ApiResponse").field(&self.result).finish()
+    }
+}
+
+impl<T: Serialize, E: Envelope> Serialize for ApiResponse<T, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize::<T, E, S>(&self.result, serializer)
+    }
+}
+
+impl<'de, T: DeserializeOwned, E: Envelope> Deserialize<'de> for ApiResponse<T, E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize::<T, E, D>(deserializer).map(Into::into)
+    }
+}
+
+/// The envelope code of `err`: its error code, or the HTTP status of its
+/// kind when it has none, so that no error looks like a success.
+fn error_code<E: Envelope>(err: &WidError) -> i64 {
+    let code = if err.code.is_none() {
+        err.kind.http_status() as i64
+    } else {
+        err.code.get() as i64
+    };
+    if code == E::SUCCESS_CODE {
+        -1
+    } else {
+        code
+    }
+}
+
+/// Serializes a `Result<T, WidError>` in the envelope `E`, for
+/// `#[serde(serialize_with = "widerror::api::serialize::<_, MyEnvelope, _>")]`.
+pub fn serialize<T, E, S>(result: &Result<T, WidError>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    E: Envelope,
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(None)?;
+    match result {
+        Ok(data) => {
+            map.serialize_entry(E::CODE, &E::SUCCESS_CODE)?;
+            map.serialize_entry(E::MESSAGE, E::SUCCESS_MESSAGE)?;
+            map.serialize_entry(E::DATA, data)?;
+        }
+        Err(err) => {
+            let public;
+            let err = match E::public_policy() {
+                Some(policy) => {
+                    public = err.to_public(&policy);
+                    &public
+                }
+                None => err,
+            };
+            map.serialize_entry(E::CODE, &error_code::<E>(err))?;
+            map.serialize_entry(E::MESSAGE, &err.message.to_string())?;
+            if E::DATA_ON_ERROR {
+                map.serialize_entry(E::DATA, &())?;
+            }
+            if let Some(key) = E::ERROR {
+                let err = serde_json::to_value(err).map_err(S::Error::custom)?;
+                map.serialize_entry(key, &err)?;
+            }
+        }
+    }
+    map.end()
+}
+
+/// Reads a `Result<T, WidError>` from the envelope `E`.
+///
+/// Errors are rebuilt from the `ERROR` member when it holds a `WidError`,
+/// and otherwise from whatever code and message the body has, so that
+/// unknown error shapes still give a `WidError`.
+pub fn deserialize<'de, T, E, D>(deserializer: D) -> Result<Result<T, WidError>, D::Error>
+where
+    T: DeserializeOwned,
+    E: Envelope,
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    let map = match &value {
+        Value::Object(map) => map,
+        _ => return Ok(Err(foreign(None, &value))),
+    };
+    let code = map.get(E::CODE).and_then(Value::as_i64);
+    let success = match code {
+        Some(code) => code == E::SUCCESS_CODE,
+        None => map.contains_key(E::DATA) && error_member::<E>(map).is_none(),
+    };
+    if success {
+        let data = map.get(E::DATA).cloned().unwrap_or(Value::Null);
+        return T::deserialize(data).map(Ok).map_err(D::Error::custom);
+    }
+    if let Some(err) = error_member::<E>(map).and_then(|v| WidError::deserialize(v).ok()) {
+        return Ok(Err(err));
+    }
+    let text = map
+        .get(E::MESSAGE)
+        .or_else(|| error_member::<E>(map))
+        .unwrap_or(&value);
+    Ok(Err(foreign(code, text)))
+}
+
+fn error_member<E: Envelope>(map: &Map<String, Value>) -> Option<&Value> {
+    map.get(E::ERROR?)
+}
+
+/// An error from a body that does not hold a `WidError`: a valid code is
+/// kept as the error code, any other in `mapping_code`.
+fn foreign(code: Option<i64>, text: &Value) -> WidError {
+    let text = match text {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    let error_code = code
+        .and_then(|c| u32::try_from(c).ok())
+        .and_then(|c| ErrorCode::try_new(c).ok())
+        .unwrap_or(ErrorCode::NONE);
+    let mut err = WidError::new(error_code, Message::Default(text.into()));
+    if error_code.is_none() {
+        err.mapping_code = code.unwrap_or_default();
+    }
+    err
+}
+
+/// Serde helpers for a `Result<T, WidError>` field in the default envelope:
+/// `#[serde(with = "widerror::api::result")]`.
+pub mod result {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserializer, Serialize, Serializer};
+
+    use super::DefaultEnvelope;
+    use crate::WidError;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        result: &Result<T, WidError>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize::<T, DefaultEnvelope, S>(result, serializer)
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Result<T, WidError>, D::Error> {
+        super::deserialize::<T, DefaultEnvelope, D>(deserializer)
+    }
+}
@@ -0,0 +1,102 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::panic::Location;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::WidError;
+
+/// Whether new errors capture a backtrace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum BacktraceCapture {
+    /// Follow `RUST_LIB_BACKTRACE` / `RUST_BACKTRACE`, like `Backtrace::capture`.
+    #[default]
+    Env = 0,
+    Always = 1,
+    Never = 2,
+}
+
+static BACKTRACE_CAPTURE: AtomicU8 = AtomicU8::new(BacktraceCapture::Env as u8);
+
+/// Sets the crate-wide backtrace switch.
+pub fn set_backtrace_capture(mode: BacktraceCapture) {
+    BACKTRACE_CAPTURE.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn backtrace_capture() -> BacktraceCapture {
+    match BACKTRACE_CAPTURE.load(Ordering::Relaxed) {
+        1 => BacktraceCapture::Always,
+        2 => BacktraceCapture::Never,
+        _ => BacktraceCapture::Env,
+    }
+}
+
+pub(crate) fn capture_backtrace() -> Option<Arc<Backtrace>> {
+    let backtrace = match backtrace_capture() {
+        BacktraceCapture::Env => Backtrace::capture(),
+        BacktraceCapture::Always => Backtrace::force_capture(),
+        BacktraceCapture::Never => return None,
+    };
+    (backtrace.status() == BacktraceStatus::Captured).then(|| Arc::new(backtrace))
+}
+
+impl WidError {
+    /// Where the error was created, or where its source was attached.
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        self.location
+    }
+
+    /// Backtrace taken at creation, if capture was enabled.
+    ///
+    /// Both are also offered through `Error::provide` when built on nightly
+    /// with `RUSTFLAGS="--cfg widerror_nightly"`.
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+
+    /// Serializes the error with an extra `debug` section holding the
+    /// location and traces, which the plain representation never includes.
+    pub fn debug_view(&self) -> DebugView<'_> {
+        DebugView {
+            error: self,
+            debug: DebugSection {
+                location: self.location.map(|l| LocationRepr {
+                    file: l.file(),
+                    line: l.line(),
+                    column: l.column(),
+                }),
+                backtrace: self.backtrace.as_ref().map(|b| b.to_string()),
+                #[cfg(feature = "tracing")]
+                span_trace: self.span_trace.as_ref().map(|t| t.to_string()),
+            },
+        }
+    }
+}
+
+/// See [`WidError::debug_view`].
+#[derive(Serialize, Debug)]
+pub struct DebugView<'a> {
+    #[serde(flatten)]
+    error: &'a WidError,
+    debug: DebugSection,
+}
+
+#[derive(Serialize, Debug)]
+struct DebugSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<LocationRepr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backtrace: Option<String>,
+    #[cfg(feature = "tracing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span_trace: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct LocationRepr {
+    file: &'static str,
+    line: u32,
+    column: u32,
+}
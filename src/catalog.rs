@@ -0,0 +1,664 @@
+//! Error catalogs: the errors of a service declared in one TOML file that
+//! people can read, validated and turned into Rust code at build time.
+//!
+//! ```toml
+//! [[namespace]]
+//! code = 10001
+//! name = "order"
+//! description = "Order service."
+//!
+//! [[namespace.error]]
+//! code = 100010001
+//! name = "ORDER_NOT_FOUND"
+//! kind = "NOT_FOUND"
+//! scope = "CLIENTSIDE"
+//! level = "ERROR"
+//! retry_mode = "DENIED"
+//! pass_through_mode = "SHOULD"
+//! mapping_code = 404
+//! message = "order {id} not found"
+//! i18n = "order.not_found"
+//! description = "The order does not exist or was deleted."
+//! ```
+//!
+//! Only `code`, `name`, `kind` and `message` are required; `level` is a number
+//! or a [`Severity`] name. Errors that are no longer returned keep their
+//! entry with a `deprecated = "..."` note, and codes that were removed are
+//! listed in the namespace's `retired = [...]` so that they are never reused.
+//! In `build.rs`:
+//!
+//! ```no_run
+//! if let Err(e) = widerror::catalog::build("errors.toml") {
+//!     panic!("{}", e);
+//! }
+//! ```
+//!
+//! then `include!(concat!(env!("OUT_DIR"), "/errors.rs"));` gives a module
+//! per namespace with an [`ErrorDef`] const and a constructor per error,
+//! taking the message arguments (`order::ORDER_NOT_FOUND`,
+//! `order::order_not_found(id)`), and `ERRORS` listing every definition.
+//! Message arguments must therefore be lower snake case.
+
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write as _};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::message::argument_names;
+use crate::registry::{ErrorDef, Registry, RegistryError};
+use crate::{CanonicalName, ErrorCode, Kind, Namespace, Severity};
+
+pub mod codegen;
+pub mod diff;
+pub mod docs;
+
+/// A validated catalog, in file order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Catalog {
+    pub namespaces: Vec<NamespaceDef>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceDef {
+    pub namespace: Namespace,
+    /// lower snake case, the module of the generated code
+    pub name: String,
+    pub description: Option<String>,
+    pub errors: Vec<ErrorDef>,
+    /// codes that were removed and must never be reused
+    pub retired: Vec<ErrorCode>,
+}
+
+/// A problem found in a catalog file. Lines and columns start at 1; line 0
+/// is the file as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Everything wrong with a catalog file, one diagnostic per line when
+/// displayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogError {
+    pub path: Option<PathBuf>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CatalogError {
+    fn whole_file(path: &Path, message: String) -> CatalogError {
+        CatalogError {
+            path: Some(path.to_path_buf()),
+            diagnostics: vec![Diagnostic {
+                line: 0,
+                column: 0,
+                message,
+            }],
+        }
+    }
+}
+
+impl Display for CatalogError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, diagnostic) in self.diagnostics.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let mut prefix = match &self.path {
+                Some(path) => format!("{}:", path.display()),
+                None => String::new(),
+            };
+            if diagnostic.line > 0 {
+                write!(prefix, "{}:{}:", diagnostic.line, diagnostic.column)?;
+            }
+            if !prefix.is_empty() {
+                write!(f, "{} ", prefix)?;
+            }
+            f.write_str(&diagnostic.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for CatalogError {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCatalog {
+    #[serde(default)]
+    namespace: Vec<RawNamespace>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNamespace {
+    code: Spanned<u32>,
+    name: Spanned<String>,
+    description: Option<String>,
+    #[serde(default)]
+    retired: Vec<Spanned<u32>>,
+    #[serde(default)]
+    error: Vec<RawError>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawError {
+    code: Spanned<u32>,
+    name: Spanned<String>,
+    kind: Spanned<String>,
+    message: Spanned<String>,
+    scope: Option<Spanned<String>>,
+    level: Option<Spanned<toml::Value>>,
+    retry_mode: Option<Spanned<String>>,
+    pass_through_mode: Option<Spanned<String>>,
+    #[serde(default)]
+    mapping_code: i64,
+    i18n: Option<String>,
+    description: Option<String>,
+    deprecated: Option<String>,
+}
+
+/// Collects diagnostics while a catalog is validated.
+struct Checker<'a> {
+    source: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    /// 1-based line and column of a byte offset.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let before = self.source.get(..offset).unwrap_or(self.source);
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+
+    fn line(&self, span: &Range<usize>) -> usize {
+        self.position(span.start).0
+    }
+
+    fn report(&mut self, span: Range<usize>, message: String) {
+        let (line, column) = self.position(span.start);
+        self.diagnostics.push(Diagnostic {
+            line,
+            column,
+            message,
+        });
+    }
+
+    /// Checks that an error code has 9 digits and starts with `namespace`.
+    fn code(&mut self, value: &Spanned<u32>, namespace: Namespace) -> ErrorCode {
+        let code = match ErrorCode::try_new(*value.get_ref()) {
+            Ok(code) if !code.is_none() => code,
+            _ => {
+                self.report(
+                    value.span(),
+                    format!("error code {} does not have 9 digits", value.get_ref()),
+                );
+                return ErrorCode::NONE;
+            }
+        };
+        if !namespace.is_none() && !namespace.contains(code) {
+            self.report(
+                value.span(),
+                format!(
+                    "error code {} does not start with its namespace {}",
+                    code, namespace
+                ),
+            );
+        }
+        code
+    }
+
+    /// Parses a canonical name, reporting unknown ones with the valid names.
+    fn name<T: CanonicalName + Default>(&mut self, value: Option<&Spanned<String>>) -> T {
+        let Some(value) = value else {
+            return T::default();
+        };
+        value.get_ref().parse().unwrap_or_else(|e| {
+            let expected: Vec<&str> = T::ALL.iter().map(|v| v.as_str()).collect();
+            self.report(
+                value.span(),
+                format!("{}, expected one of {}", e, expected.join(", ")),
+            );
+            T::default()
+        })
+    }
+
+    fn level(&mut self, value: Option<&Spanned<toml::Value>>) -> u8 {
+        let Some(value) = value else {
+            return 0;
+        };
+        let level = match value.get_ref() {
+            toml::Value::Integer(n) => u8::try_from(*n).ok(),
+            toml::Value::String(name) => name.parse::<Severity>().ok().map(Severity::level),
+            _ => None,
+        };
+        level.unwrap_or_else(|| {
+            self.report(
+                value.span(),
+                "level must be a number in [0, 255] or a severity name".to_string(),
+            );
+            0
+        })
+    }
+}
+
+fn is_module_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !matches!(name, "self" | "super" | "crate")
+}
+
+fn is_const_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && is_module_name(&name.to_ascii_lowercase())
+}
+
+impl Catalog {
+    /// Parses and validates a catalog, reporting every problem found:
+    /// invalid codes, codes outside their namespace, duplicate or retired
+    /// codes, duplicate and malformed names, and unknown kinds, scopes and
+    /// modes.
+    pub fn parse(source: &str) -> Result<Catalog, CatalogError> {
+        let mut checker = Checker {
+            source,
+            diagnostics: Vec::new(),
+        };
+        let raw: RawCatalog = match toml::from_str(source) {
+            Ok(raw) => raw,
+            Err(e) => {
+                checker.report(e.span().unwrap_or(0..0), e.message().to_string());
+                return Err(CatalogError {
+                    path: None,
+                    diagnostics: checker.diagnostics,
+                });
+            }
+        };
+
+        let mut namespace_codes = HashMap::new();
+        let mut namespace_names = HashMap::new();
+        let mut codes: HashMap<u32, (String, usize)> = HashMap::new();
+        let mut retired_codes = HashMap::new();
+        let mut names = HashMap::new();
+        let mut namespaces = Vec::new();
+        for raw_ns in raw.namespace {
+            let line = checker.line(&raw_ns.code.span());
+            let value = *raw_ns.code.get_ref();
+            let namespace = match Namespace::try_new(value) {
+                Ok(namespace) if !namespace.is_none() => namespace,
+                _ => {
+                    checker.report(
+                        raw_ns.code.span(),
+                        format!("namespace {} does not have 5 digits", value),
+                    );
+                    Namespace::NONE
+                }
+            };
+            match namespace_codes.entry(value) {
+                Entry::Occupied(first) => checker.report(
+                    raw_ns.code.span(),
+                    format!(
+                        "namespace {} is already defined at line {}",
+                        value,
+                        first.get()
+                    ),
+                ),
+                Entry::Vacant(entry) => {
+                    entry.insert(line);
+                }
+            }
+            let name = raw_ns.name.get_ref().clone();
+            if !is_module_name(&name) {
+                checker.report(
+                    raw_ns.name.span(),
+                    format!("namespace name `{}` is not lower snake case", name),
+                );
+            }
+            let line = checker.line(&raw_ns.name.span());
+            match namespace_names.entry(name.clone()) {
+                Entry::Occupied(first) => checker.report(
+                    raw_ns.name.span(),
+                    format!(
+                        "namespace name `{}` is already used at line {}",
+                        name,
+                        first.get()
+                    ),
+                ),
+                Entry::Vacant(entry) => {
+                    entry.insert(line);
+                }
+            }
+
+            let mut retired = Vec::new();
+            for raw_code in &raw_ns.retired {
+                let code = checker.code(raw_code, namespace);
+                let line = checker.line(&raw_code.span());
+                if let Some(first) = retired_codes.insert(*raw_code.get_ref(), line) {
+                    checker.report(
+                        raw_code.span(),
+                        format!(
+                            "error code {} is already retired at line {}",
+                            raw_code.get_ref(),
+                            first
+                        ),
+                    );
+                }
+                retired.push(code);
+            }
+
+            let mut errors = Vec::new();
+            for raw_err in raw_ns.error {
+                let value = *raw_err.code.get_ref();
+                let code = checker.code(&raw_err.code, namespace);
+                if let Some(line) = retired_codes.get(&value) {
+                    checker.report(
+                        raw_err.code.span(),
+                        format!("error code {} was retired at line {}", value, line),
+                    );
+                }
+                let name = raw_err.name.get_ref().clone();
+                let line = checker.line(&raw_err.code.span());
+                match codes.entry(value) {
+                    Entry::Occupied(first) => {
+                        let (first_name, first_line) = first.get();
+                        checker.report(
+                            raw_err.code.span(),
+                            format!(
+                                "error code {} is already used by {} at line {}",
+                                value, first_name, first_line
+                            ),
+                        );
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert((name.clone(), line));
+                    }
+                }
+                if !is_const_name(&name) {
+                    checker.report(
+                        raw_err.name.span(),
+                        format!("error name `{}` is not upper snake case", name),
+                    );
+                }
+                let line = checker.line(&raw_err.name.span());
+                match names.entry(name.clone()) {
+                    Entry::Occupied(first) => checker.report(
+                        raw_err.name.span(),
+                        format!(
+                            "error name `{}` is already used at line {}",
+                            name,
+                            first.get()
+                        ),
+                    ),
+                    Entry::Vacant(entry) => {
+                        entry.insert(line);
+                    }
+                }
+
+                // Arguments become parameters of the generated constructor.
+                for arg in argument_names(raw_err.message.get_ref()) {
+                    if !is_module_name(arg) {
+                        checker.report(
+                            raw_err.message.span(),
+                            format!("message argument `{}` is not lower snake case", arg),
+                        );
+                    }
+                }
+
+                errors.push(ErrorDef {
+                    code,
+                    name: Cow::Owned(name),
+                    namespace,
+                    kind: checker.name::<Kind>(Some(&raw_err.kind)),
+                    message: Cow::Owned(raw_err.message.into_inner()),
+                    scope: checker.name(raw_err.scope.as_ref()),
+                    level: checker.level(raw_err.level.as_ref()),
+                    retry_mode: checker.name(raw_err.retry_mode.as_ref()),
+                    pass_through_mode: checker.name(raw_err.pass_through_mode.as_ref()),
+                    mapping_code: raw_err.mapping_code,
+                    i18n: raw_err.i18n.map(Cow::Owned),
+                    description: raw_err.description.map(Cow::Owned),
+                    deprecated: raw_err.deprecated.map(Cow::Owned),
+                });
+            }
+            namespaces.push(NamespaceDef {
+                namespace,
+                name,
+                description: raw_ns.description,
+                errors,
+                retired,
+            });
+        }
+
+        if checker.diagnostics.is_empty() {
+            Ok(Catalog { namespaces })
+        } else {
+            Err(CatalogError {
+                path: None,
+                diagnostics: checker.diagnostics,
+            })
+        }
+    }
+
+    /// Reads and validates a catalog file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Catalog, CatalogError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| CatalogError::whole_file(path, e.to_string()))?;
+        Catalog::parse(&source).map_err(|mut e| {
+            e.path = Some(path.to_path_buf());
+            e
+        })
+    }
+
+    /// Every definition, in file order.
+    pub fn errors(&self) -> impl Iterator<Item = &ErrorDef> {
+        self.namespaces.iter().flat_map(|ns| ns.errors.iter())
+    }
+
+    pub fn to_registry(&self) -> Result<Registry, Vec<RegistryError>> {
+        Registry::from_defs(self.errors().cloned())
+    }
+
+    /// The definitions grouped by namespace, in code order, for sources
+    /// without a catalog file such as a [`Registry`]. Namespaces are named
+    /// `namespace_{code}`.
+    pub fn from_defs<I: IntoIterator<Item = ErrorDef>>(defs: I) -> Catalog {
+        let mut grouped: BTreeMap<Namespace, Vec<ErrorDef>> = BTreeMap::new();
+        for def in defs {
+            grouped.entry(def.namespace).or_default().push(def);
+        }
+        let namespaces = grouped
+            .into_iter()
+            .map(|(namespace, mut errors)| {
+                errors.sort_by_key(|def| def.code);
+                NamespaceDef {
+                    namespace,
+                    name: format!("namespace_{}", namespace),
+                    description: None,
+                    errors,
+                    retired: Vec::new(),
+                }
+            })
+            .collect();
+        Catalog { namespaces }
+    }
+
+    /// Rust source with a module per namespace holding its `NAMESPACE`, an
+    /// `ErrorDef` const and a constructor per error, followed by `ERRORS`.
+    pub fn to_rust(&self) -> String {
+        let mut out = String::from("// @generated by widerror::catalog. Do not edit.\n");
+        for ns in &self.namespaces {
+            out.push('\n');
+            if let Some(description) = &ns.description {
+                write_doc(&mut out, "", description);
+            }
+            let _ = writeln!(out, "pub mod {} {{", ident(&ns.name));
+            let _ = writeln!(
+                out,
+                "    pub const NAMESPACE: ::widerror::Namespace = ::widerror::Namespace::new({});",
+                ns.namespace
+            );
+            for def in &ns.errors {
+                write_error(&mut out, def);
+            }
+            out.push_str("}\n");
+        }
+        out.push_str("\n/// Every definition of the catalog.\n");
+        out.push_str("pub const ERRORS: &[::widerror::registry::ErrorDef] = &[\n");
+        for ns in &self.namespaces {
+            for def in &ns.errors {
+                let _ = writeln!(out, "    {}::{},", ident(&ns.name), def.name);
+            }
+        }
+        out.push_str("];\n");
+        out
+    }
+}
+
+fn write_doc(out: &mut String, indent: &str, text: &str) {
+    for line in text.lines() {
+        let _ = writeln!(
+            out,
+            "{}///{}{}",
+            indent,
+            if line.is_empty() { "" } else { " " },
+            line
+        );
+    }
+}
+
+fn write_error(out: &mut String, def: &ErrorDef) {
+    let optional = |value: &Option<Cow<'static, str>>| match value {
+        Some(text) => format!("Some(::std::borrow::Cow::Borrowed({:?}))", text),
+        None => "None".to_string(),
+    };
+    out.push('\n');
+    write_doc(
+        out,
+        "    ",
+        def.description.as_deref().unwrap_or(def.message.as_ref()),
+    );
+    let _ = writeln!(
+        out,
+        "    pub const {}: ::widerror::registry::ErrorDef = ::widerror::registry::ErrorDef {{",
+        def.name
+    );
+    let fields = [
+        ("code", format!("::widerror::ErrorCode::new({})", def.code)),
+        (
+            "name",
+            format!("::std::borrow::Cow::Borrowed({:?})", def.name),
+        ),
+        ("namespace", "NAMESPACE".to_string()),
+        ("kind", format!("::widerror::Kind::{:?}", def.kind)),
+        (
+            "message",
+            format!("::std::borrow::Cow::Borrowed({:?})", def.message),
+        ),
+        ("scope", format!("::widerror::Scope::{:?}", def.scope)),
+        ("level", def.level.to_string()),
+        (
+            "retry_mode",
+            format!("::widerror::RetryMode::{:?}", def.retry_mode),
+        ),
+        (
+            "pass_through_mode",
+            format!("::widerror::PassThroughMode::{:?}", def.pass_through_mode),
+        ),
+        ("mapping_code", def.mapping_code.to_string()),
+        ("i18n", optional(&def.i18n)),
+        ("description", optional(&def.description)),
+        ("deprecated", optional(&def.deprecated)),
+    ];
+    for (field, value) in fields {
+        let _ = writeln!(out, "        {}: {},", field, value);
+    }
+    out.push_str("    };\n\n");
+
+    let args = argument_names(&def.message);
+    let _ = writeln!(out, "    /// Creates [`{}`].", def.name);
+    if let Some(note) = &def.deprecated {
+        let _ = writeln!(out, "    #[deprecated(note = {:?})]", note);
+    }
+    out.push_str("    #[track_caller]\n");
+    let params: Vec<String> = args
+        .iter()
+        .map(|arg| format!("{}: impl Into<::widerror::Arg>", ident(arg)))
+        .collect();
+    let _ = writeln!(
+        out,
+        "    pub fn {}({}) -> ::widerror::WidError {{",
+        ident(&def.name.to_ascii_lowercase()),
+        params.join(", ")
+    );
+    if args.is_empty() {
+        let _ = writeln!(out, "        {}.to_error()", def.name);
+    } else {
+        let _ = writeln!(out, "        let mut __err = {}.to_error();", def.name);
+        for arg in &args {
+            let _ = writeln!(
+                out,
+                "        __err.message.template_mut().args.insert({:?}, {});",
+                arg,
+                ident(arg)
+            );
+        }
+        out.push_str("        __err\n");
+    }
+    out.push_str("    }\n");
+}
+
+/// `name`, raw when it is a keyword.
+fn ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Validates the catalog at `path` and writes its Rust code to
+/// `$OUT_DIR/<file stem>.rs`, for build scripts. Returns the written file.
+pub fn build<P: AsRef<Path>>(path: P) -> Result<PathBuf, CatalogError> {
+    let path = path.as_ref();
+    println!("cargo:rerun-if-changed={}", path.display());
+    let catalog = Catalog::load(path)?;
+    let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+        CatalogError::whole_file(
+            path,
+            "OUT_DIR is not set; call build() from build.rs".into(),
+        )
+    })?;
+    let stem = path.file_stem().unwrap_or("catalog".as_ref());
+    let out = Path::new(&out_dir).join(format!("{}.rs", stem.to_string_lossy()));
+    std::fs::write(&out, catalog.to_rust())
+        .map_err(|e| CatalogError::whole_file(path, e.to_string()))?;
+    Ok(out)
+}
@@ -0,0 +1,622 @@
+//! Catalog constants for other languages: TypeScript, Go, Kotlin, Java and a
+//! JSON manifest, rendered from overridable templates.
+//!
+//! ```
+//! use widerror::catalog::codegen::Generator;
+//! use widerror::catalog::Catalog;
+//!
+//! let catalog = Catalog::parse(r#"
+//! [[namespace]]
+//! code = 10001
+//! name = "order"
+//!
+//! [[namespace.error]]
+//! code = 100010001
+//! name = "ORDER_NOT_FOUND"
+//! kind = "NOT_FOUND"
+//! message = "order not found"
+//! "#).unwrap();
+//! let go = Generator::go().render(&catalog).unwrap();
+//! assert!(go.contains("const ErrOrderNotFound uint32 = 100010001\n"));
+//!
+//! let names = Generator::go()
+//!     .with_template("{{#errors}}{{code}} {{name}}\n{{/errors}}")
+//!     .render(&catalog)
+//!     .unwrap();
+//! assert_eq!(names, "100010001 ORDER_NOT_FOUND\n");
+//! ```
+//!
+//! Templates use a subset of Mustache: `{{var}}` inserts a value as is,
+//! `{{#list}}...{{/list}}` repeats for each item, `{{#var}}...{{/var}}` and
+//! `{{^var}}...{{/var}}` keep their content when the value is (not) true or
+//! non-empty. Lines that hold only a section tag are removed.
+//!
+//! The root has `package`, `namespaces` and `errors`, every error of the
+//! catalog. A namespace has `code`, `name`, `pascal_name`, `description` and
+//! its `errors`. An error has `code`, `name`, `pascal_name`, `camel_name`,
+//! `kind`, `status` (HTTP), `scope`, `level`, `severity`, `retry_mode`,
+//! `pass_through_mode`, `mapping_code`, `message`, `i18n`, `description`,
+//! `deprecated`, `namespace`, `namespace_name` and `namespace_pascal_name`.
+//! `message`, `i18n`, `description` and `deprecated` are on one line with
+//! `*/` escaped as `*\/`, so that they fit in any comment, and also come as
+//! exact string literals of the target language with a `_str` suffix. List
+//! items have `first` and `last`.
+//!
+//! Namespaces and errors are rendered in code order, so the output only
+//! changes with the catalog.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use super::{Catalog, NamespaceDef};
+use crate::registry::ErrorDef;
+use crate::Severity;
+
+/// A template and the way it quotes strings.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    pub template: Cow<'static, str>,
+    /// Value of the `package` variable.
+    pub package: String,
+    /// Turns text into a string literal of the target language.
+    pub quote: fn(&str) -> String,
+}
+
+const TYPESCRIPT: &str = r#"// @generated by widerror from the error catalog. Do not edit.
+
+export interface ErrorDef {
+  readonly code: number;
+  readonly name: string;
+  readonly kind: string;
+  readonly message: string;
+}
+{{#namespaces}}
+
+{{#description}}
+/** {{description}} */
+{{/description}}
+export const {{pascal_name}}Errors = {
+{{#errors}}
+  {{name}}: { code: {{code}}, name: "{{name}}", kind: "{{kind}}", message: {{message_str}} },
+{{/errors}}
+} as const satisfies Record<string, ErrorDef>;
+
+export type {{pascal_name}}ErrorName = keyof typeof {{pascal_name}}Errors;
+{{/namespaces}}
+
+/** Every error code of the catalog. */
+export type ErrorCode =
+{{#errors}}
+  | {{code}}{{#last}};{{/last}}
+{{/errors}}
+{{^errors}}
+  never;
+{{/errors}}
+"#;
+
+const GO: &str = r#"// Code generated by widerror from the error catalog. DO NOT EDIT.
+
+package {{package}}
+
+// ErrorDef describes one error code.
+type ErrorDef struct {
+	Code    uint32
+	Name    string
+	Kind    string
+	Message string
+}
+{{#namespaces}}
+
+{{#description}}
+// {{description}}
+{{/description}}
+{{#errors}}
+const Err{{pascal_name}} uint32 = {{code}}
+{{/errors}}
+{{/namespaces}}
+
+// Errors holds every error definition in code order.
+var Errors = []ErrorDef{
+{{#errors}}
+	{Code: Err{{pascal_name}}, Name: "{{name}}", Kind: "{{kind}}", Message: {{message_str}}},
+{{/errors}}
+}
+"#;
+
+const KOTLIN: &str = r#"// @generated by widerror from the error catalog. Do not edit.
+{{#package}}
+
+package {{package}}
+{{/package}}
+
+sealed class ErrorDef(val code: Int, val name: String, val kind: String, val message: String) {
+{{#namespaces}}
+{{#description}}
+    /** {{description}} */
+{{/description}}
+    sealed class {{pascal_name}}(code: Int, name: String, kind: String, message: String) :
+        ErrorDef(code, name, kind, message) {
+{{#errors}}
+        object {{pascal_name}} : {{namespace_pascal_name}}({{code}}, "{{name}}", "{{kind}}", {{message_str}})
+{{/errors}}
+    }
+
+{{/namespaces}}
+    companion object {
+        val all: List<ErrorDef> by lazy {
+            listOf(
+{{#errors}}
+                {{namespace_pascal_name}}.{{pascal_name}},
+{{/errors}}
+            )
+        }
+
+        fun byCode(code: Int): ErrorDef? = all.find { it.code == code }
+    }
+}
+"#;
+
+const JAVA: &str = r#"// @generated by widerror from the error catalog. Do not edit.
+{{#package}}
+
+package {{package}};
+{{/package}}
+
+public sealed interface ErrorDef {
+    int code();
+
+    String name();
+
+    String kind();
+
+    String message();
+{{#namespaces}}
+
+{{#description}}
+    /** {{description}} */
+{{/description}}
+    enum {{pascal_name}} implements ErrorDef {
+{{#errors}}
+        {{name}}({{code}}, "{{kind}}", {{message_str}}),
+{{/errors}}
+        ;
+
+        private final int code;
+        private final String kind;
+        private final String message;
+
+        {{pascal_name}}(int code, String kind, String message) {
+            this.code = code;
+            this.kind = kind;
+            this.message = message;
+        }
+
+        public int code() {
+            return code;
+        }
+
+        public String kind() {
+            return kind;
+        }
+
+        public String message() {
+            return message;
+        }
+    }
+{{/namespaces}}
+}
+"#;
+
+const JSON: &str = r#"{
+  "namespaces": [
+{{#namespaces}}
+    {
+      "code": {{code}},
+      "name": "{{name}}",
+      "description": {{description_str}},
+      "errors": [
+{{#errors}}
+        {
+          "code": {{code}},
+          "name": "{{name}}",
+          "kind": "{{kind}}",
+          "status": {{status}},
+          "scope": "{{scope}}",
+          "level": {{level}},
+          "retry_mode": "{{retry_mode}}",
+          "pass_through_mode": "{{pass_through_mode}}",
+          "mapping_code": {{mapping_code}},
+          "message": {{message_str}},
+          "i18n": {{i18n_str}},
+          "description": {{description_str}},
+          "deprecated": {{deprecated_str}}
+        }{{^last}},{{/last}}
+{{/errors}}
+      ]
+    }{{^last}},{{/last}}
+{{/namespaces}}
+  ]
+}
+"#;
+
+const DESCRIPTION: [&str; 2] = ["description", "description_str"];
+
+/// A JSON string literal, which TypeScript, Go and Java read the same way.
+fn json_quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+/// A JSON string literal with `$` escaped against string templates.
+fn kotlin_quote(text: &str) -> String {
+    json_quote(text).replace('$', "\\$")
+}
+
+impl Generator {
+    pub fn new(template: &str, quote: fn(&str) -> String) -> Generator {
+        Generator {
+            template: Cow::Owned(template.to_string()),
+            package: String::new(),
+            quote,
+        }
+    }
+
+    /// A `const` object per namespace and an `ErrorCode` union type.
+    pub fn typescript() -> Generator {
+        Generator {
+            template: Cow::Borrowed(TYPESCRIPT),
+            package: String::new(),
+            quote: json_quote,
+        }
+    }
+
+    /// `Err`-prefixed constants and an `Errors` slice, in package `errcodes`.
+    pub fn go() -> Generator {
+        Generator {
+            template: Cow::Borrowed(GO),
+            package: "errcodes".to_string(),
+            quote: json_quote,
+        }
+    }
+
+    /// A sealed class per namespace with an object per error.
+    pub fn kotlin() -> Generator {
+        Generator {
+            template: Cow::Borrowed(KOTLIN),
+            package: String::new(),
+            quote: kotlin_quote,
+        }
+    }
+
+    /// A sealed interface implemented by an enum per namespace.
+    pub fn java() -> Generator {
+        Generator {
+            template: Cow::Borrowed(JAVA),
+            package: String::new(),
+            quote: json_quote,
+        }
+    }
+
+    /// Every attribute of every error.
+    pub fn json() -> Generator {
+        Generator {
+            template: Cow::Borrowed(JSON),
+            package: String::new(),
+            quote: json_quote,
+        }
+    }
+
+    /// Replaces the template, keeping the quoting.
+    pub fn with_template(mut self, template: &str) -> Generator {
+        self.template = Cow::Owned(template.to_string());
+        self
+    }
+
+    pub fn with_package(mut self, package: &str) -> Generator {
+        self.package = package.to_string();
+        self
+    }
+
+    pub fn render(&self, catalog: &Catalog) -> Result<String, TemplateError> {
+        let nodes = parse(&self.template)?;
+        let mut namespaces: Vec<&NamespaceDef> = catalog.namespaces.iter().collect();
+        namespaces.sort_by_key(|ns| ns.namespace);
+        let mut errors: Vec<(&NamespaceDef, &ErrorDef)> = namespaces
+            .iter()
+            .flat_map(|ns| ns.errors.iter().map(move |def| (*ns, def)))
+            .collect();
+        errors.sort_by_key(|(_, def)| def.code);
+
+        let mut root = Context::new();
+        root.insert("package", Value::Text(self.package.clone()));
+        let namespaces = namespaces
+            .iter()
+            .map(|ns| {
+                let mut defs: Vec<(&NamespaceDef, &ErrorDef)> =
+                    ns.errors.iter().map(|def| (*ns, def)).collect();
+                defs.sort_by_key(|(_, def)| def.code);
+                let mut context = Context::new();
+                context.insert("code", Value::Text(ns.namespace.to_string()));
+                context.insert("name", Value::Text(ns.name.clone()));
+                context.insert("pascal_name", Value::Text(pascal_case(&ns.name)));
+                self.insert_text(&mut context, DESCRIPTION, ns.description.as_deref());
+                context.insert("errors", self.error_list(&defs));
+                context
+            })
+            .collect();
+        root.insert("namespaces", list(namespaces));
+        root.insert("errors", self.error_list(&errors));
+
+        let mut out = String::new();
+        render(&nodes, &mut vec![&root], &mut out)?;
+        Ok(out)
+    }
+
+    /// `name` as one line of text and `literal` as a string literal, `null`
+    /// when there is no value.
+    fn insert_text(
+        &self,
+        context: &mut Context,
+        [name, literal]: [&'static str; 2],
+        value: Option<&str>,
+    ) {
+        let text = value.unwrap_or_default().split_whitespace();
+        let text = text.collect::<Vec<_>>().join(" ").replace("*/", "*\\/");
+        context.insert(name, Value::Text(text));
+        let quoted = value.map_or("null".to_string(), self.quote);
+        context.insert(literal, Value::Text(quoted));
+    }
+
+    fn error_list(&self, defs: &[(&NamespaceDef, &ErrorDef)]) -> Value {
+        let items = defs
+            .iter()
+            .map(|(ns, def)| {
+                let mut context = Context::new();
+                let mut text = |name: &'static str, value: String| {
+                    context.insert(name, Value::Text(value));
+                };
+                text("code", def.code.to_string());
+                text("name", def.name.to_string());
+                text("pascal_name", pascal_case(&def.name));
+                text("camel_name", camel_case(&def.name));
+                text("kind", def.kind.as_str().to_string());
+                text("status", def.kind.http_status().to_string());
+                text("scope", def.scope.as_str().to_string());
+                text("level", def.level.to_string());
+                text("severity", Severity::from_level(def.level).to_string());
+                text("retry_mode", def.retry_mode.as_str().to_string());
+                text(
+                    "pass_through_mode",
+                    def.pass_through_mode.as_str().to_string(),
+                );
+                text("mapping_code", def.mapping_code.to_string());
+                text("namespace", ns.namespace.to_string());
+                text("namespace_name", ns.name.clone());
+                text("namespace_pascal_name", pascal_case(&ns.name));
+                self.insert_text(&mut context, ["message", "message_str"], Some(&def.message));
+                self.insert_text(&mut context, ["i18n", "i18n_str"], def.i18n.as_deref());
+                self.insert_text(&mut context, DESCRIPTION, def.description.as_deref());
+                self.insert_text(
+                    &mut context,
+                    ["deprecated", "deprecated_str"],
+                    def.deprecated.as_deref(),
+                );
+                context
+            })
+            .collect();
+        list(items)
+    }
+}
+
+/// `ORDER_NOT_FOUND` or `order_not_found` → `OrderNotFound`.
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let lower = word.to_ascii_lowercase();
+            let mut chars = lower.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect()
+}
+
+/// `ORDER_NOT_FOUND` → `orderNotFound`.
+fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    chars.next().map_or(String::new(), |first| {
+        first.to_ascii_lowercase().to_string() + chars.as_str()
+    })
+}
+
+/// A malformed template or a variable it does not know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    /// Byte offset of the tag in the template.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "template error at byte {}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl Error for TemplateError {}
+
+type Context = BTreeMap<&'static str, Value>;
+
+enum Value {
+    Text(String),
+    Bool(bool),
+    List(Vec<Context>),
+}
+
+/// Items with their `first` and `last` flags.
+fn list(mut items: Vec<Context>) -> Value {
+    let len = items.len();
+    for (i, item) in items.iter_mut().enumerate() {
+        item.insert("first", Value::Bool(i == 0));
+        item.insert("last", Value::Bool(i + 1 == len));
+    }
+    Value::List(items)
+}
+
+enum Node<'t> {
+    Text(&'t str),
+    Var(&'t str, usize),
+    Section {
+        name: &'t str,
+        inverted: bool,
+        offset: usize,
+        children: Vec<Node<'t>>,
+    },
+}
+
+fn parse(template: &str) -> Result<Vec<Node<'_>>, TemplateError> {
+    // Open sections: name, inverted, offset and the nodes read so far.
+    let mut stack = vec![("", false, 0, Vec::new())];
+    let mut i = 0;
+    while let Some(found) = template[i..].find("{{") {
+        let open = i + found;
+        let close = template[open..]
+            .find("}}")
+            .map(|end| open + end)
+            .ok_or_else(|| TemplateError {
+                offset: open,
+                message: "unclosed tag".to_string(),
+            })?;
+        let tag = template[open + 2..close].trim();
+        let mut text_end = open;
+        let mut next = close + 2;
+        if tag.starts_with(['#', '^', '/']) {
+            let line_start = template[..open].rfind('\n').map_or(0, |p| p + 1);
+            let line_end = template[next..]
+                .find('\n')
+                .map_or(template.len(), |p| next + p + 1);
+            if line_start >= i
+                && template[line_start..open].trim().is_empty()
+                && template[next..line_end].trim().is_empty()
+            {
+                text_end = line_start;
+                next = line_end;
+            }
+        }
+        let nodes = &mut stack.last_mut().expect("root is never popped").3;
+        if text_end > i {
+            nodes.push(Node::Text(&template[i..text_end]));
+        }
+        if let Some(name) = tag.strip_prefix('#') {
+            stack.push((name.trim(), false, open, Vec::new()));
+        } else if let Some(name) = tag.strip_prefix('^') {
+            stack.push((name.trim(), true, open, Vec::new()));
+        } else if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            if stack.len() == 1 {
+                return Err(TemplateError {
+                    offset: open,
+                    message: format!("`{}` closes no section", name),
+                });
+            }
+            let (open_name, inverted, offset, children) = stack.pop().expect("checked above");
+            if open_name != name {
+                return Err(TemplateError {
+                    offset: open,
+                    message: format!("`{}` closes section `{}`", name, open_name),
+                });
+            }
+            let nodes = &mut stack.last_mut().expect("root is never popped").3;
+            nodes.push(Node::Section {
+                name,
+                inverted,
+                offset,
+                children,
+            });
+        } else {
+            nodes.push(Node::Var(tag, open));
+        }
+        i = next;
+    }
+    if stack.len() > 1 {
+        let (name, _, offset, _) = &stack[stack.len() - 1];
+        return Err(TemplateError {
+            offset: *offset,
+            message: format!("section `{}` is not closed", name),
+        });
+    }
+    let mut nodes = stack.pop().expect("root is never popped").3;
+    if i < template.len() {
+        nodes.push(Node::Text(&template[i..]));
+    }
+    Ok(nodes)
+}
+
+fn render(
+    nodes: &[Node<'_>],
+    stack: &mut Vec<&Context>,
+    out: &mut String,
+) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(name, offset) => match lookup(stack, name, *offset)? {
+                Value::Text(text) => out.push_str(text),
+                Value::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+                Value::List(_) => {
+                    return Err(TemplateError {
+                        offset: *offset,
+                        message: format!("`{}` is a list, use a section", name),
+                    })
+                }
+            },
+            Node::Section {
+                name,
+                inverted,
+                offset,
+                children,
+            } => match lookup(stack, name, *offset)? {
+                Value::List(items) if !inverted => {
+                    for item in items {
+                        stack.push(item);
+                        render(children, stack, out)?;
+                        stack.pop();
+                    }
+                }
+                value => {
+                    let truthy = match value {
+                        Value::Text(text) => !text.is_empty(),
+                        Value::Bool(value) => *value,
+                        Value::List(items) => !items.is_empty(),
+                    };
+                    if truthy != *inverted {
+                        render(children, stack, out)?;
+                    }
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+fn lookup<'a>(
+    stack: &[&'a Context],
+    name: &str,
+    offset: usize,
+) -> Result<&'a Value, TemplateError> {
+    stack
+        .iter()
+        .rev()
+        .find_map(|context| context.get(name))
+        .ok_or_else(|| TemplateError {
+            offset,
+            message: format!("unknown variable `{}`", name),
+        })
+}
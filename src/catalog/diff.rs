@@ -0,0 +1,385 @@
+//! Changes between two versions of a catalog, and whether clients that branch
+//! on error attributes survive them.
+//!
+//! Removing, reusing or un-retiring a code, renaming an error or changing
+//! its kind, scope, retry mode or pass-through mode is
+//! [`Compatibility::Breaking`], and so is removing or renaming a namespace;
+//! adding a namespace or an error, deprecating an error or changing its texts,
+//! level or mapping code is compatible.
+//!
+//! ```text
+//! breaking    100010001 ORDER_NOT_FOUND: kind changed from "NOT_FOUND" to "ABORTED"
+//! compatible  100010004 ORDER_PAID: added
+//!
+//! 1 breaking and 1 compatible change(s)
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use serde::{Serialize, Serializer};
+
+use super::Catalog;
+use crate::registry::ErrorDef;
+use crate::{ErrorCode, Namespace};
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    Compatible,
+    Breaking,
+}
+
+impl Display for Compatibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Compatibility::Compatible => "compatible",
+            Compatibility::Breaking => "breaking",
+        })
+    }
+}
+
+/// An attribute of an [`ErrorDef`] whose value changed.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Name,
+    Kind,
+    Scope,
+    RetryMode,
+    PassThroughMode,
+    Level,
+    MappingCode,
+    Message,
+    I18n,
+    Description,
+    Deprecated,
+}
+
+impl Field {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Kind => "kind",
+            Field::Scope => "scope",
+            Field::RetryMode => "retry_mode",
+            Field::PassThroughMode => "pass_through_mode",
+            Field::Level => "level",
+            Field::MappingCode => "mapping_code",
+            Field::Message => "message",
+            Field::I18n => "i18n",
+            Field::Description => "description",
+            Field::Deprecated => "deprecated",
+        }
+    }
+
+    /// Clients branch on names, kinds, scopes and modes.
+    pub const fn compatibility(self) -> Compatibility {
+        match self {
+            Field::Name
+            | Field::Kind
+            | Field::Scope
+            | Field::RetryMode
+            | Field::PassThroughMode => Compatibility::Breaking,
+            _ => Compatibility::Compatible,
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One change to one namespace or code. `name` is the name in the new
+/// catalog, or in the old one for removed namespaces and codes.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    NamespaceAdded {
+        namespace: Namespace,
+        name: String,
+    },
+    NamespaceRemoved {
+        namespace: Namespace,
+        name: String,
+    },
+    NamespaceRenamed {
+        namespace: Namespace,
+        old: String,
+        new: String,
+    },
+    Added {
+        code: ErrorCode,
+        name: String,
+    },
+    Removed {
+        code: ErrorCode,
+        name: String,
+    },
+    /// A code that the old catalog had retired is defined again.
+    Reused {
+        code: ErrorCode,
+        name: String,
+    },
+    /// A code that the old catalog had retired is no longer, so it may be
+    /// reused.
+    Unretired {
+        code: ErrorCode,
+    },
+    Changed {
+        code: ErrorCode,
+        name: String,
+        field: Field,
+        old: String,
+        new: String,
+    },
+}
+
+impl Change {
+    /// The code of the change, `None` for namespace changes.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Change::Added { code, .. }
+            | Change::Removed { code, .. }
+            | Change::Reused { code, .. }
+            | Change::Unretired { code }
+            | Change::Changed { code, .. } => Some(*code),
+            Change::NamespaceAdded { .. }
+            | Change::NamespaceRemoved { .. }
+            | Change::NamespaceRenamed { .. } => None,
+        }
+    }
+
+    pub fn compatibility(&self) -> Compatibility {
+        match self {
+            Change::NamespaceAdded { .. } | Change::Added { .. } => Compatibility::Compatible,
+            Change::NamespaceRemoved { .. }
+            | Change::NamespaceRenamed { .. }
+            | Change::Removed { .. }
+            | Change::Reused { .. }
+            | Change::Unretired { .. } => Compatibility::Breaking,
+            Change::Changed { field, .. } => field.compatibility(),
+        }
+    }
+}
+
+/// `code NAME: what changed`, or `namespace code name: what changed`.
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::NamespaceAdded { namespace, name } => {
+                write!(f, "namespace {} {}: added", namespace, name)
+            }
+            Change::NamespaceRemoved { namespace, name } => {
+                write!(f, "namespace {} {}: removed", namespace, name)
+            }
+            Change::NamespaceRenamed {
+                namespace,
+                old,
+                new,
+            } => write!(f, "namespace {} {}: renamed from {:?}", namespace, new, old),
+            Change::Added { code, name } => write!(f, "{} {}: added", code, name),
+            Change::Removed { code, name } => write!(f, "{} {}: removed", code, name),
+            Change::Reused { code, name } => {
+                write!(f, "{} {}: reuses a retired code", code, name)
+            }
+            Change::Unretired { code } => write!(f, "{}: no longer retired", code),
+            Change::Changed {
+                code,
+                name,
+                field,
+                old,
+                new,
+            } => write!(
+                f,
+                "{} {}: {} changed from {:?} to {:?}",
+                code, name, field, old, new
+            ),
+        }
+    }
+}
+
+/// All changes between two catalogs, by code.
+///
+/// `{}` is the human report; the JSON form is
+/// `{"breaking": bool, "changes": [{"compatibility", "change", "code", ...}]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CatalogDiff {
+    pub changes: Vec<Change>,
+}
+
+impl CatalogDiff {
+    pub fn is_breaking(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|change| change.compatibility() == Compatibility::Breaking)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for CatalogDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.changes.is_empty() {
+            return f.write_str("no changes");
+        }
+        for change in &self.changes {
+            writeln!(f, "{:<10}  {}", change.compatibility(), change)?;
+        }
+        let breaking = self.breaking().count();
+        write!(
+            f,
+            "\n{} breaking and {} compatible change(s)",
+            breaking,
+            self.changes.len() - breaking
+        )
+    }
+}
+
+impl Serialize for CatalogDiff {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Entry<'a> {
+            compatibility: Compatibility,
+            #[serde(flatten)]
+            change: &'a Change,
+        }
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            breaking: bool,
+            changes: Vec<Entry<'a>>,
+        }
+        Repr {
+            breaking: self.is_breaking(),
+            changes: self
+                .changes
+                .iter()
+                .map(|change| Entry {
+                    compatibility: change.compatibility(),
+                    change,
+                })
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+fn fields(def: &ErrorDef) -> [(Field, String); 11] {
+    let optional =
+        |text: &Option<Cow<'static, str>>| text.as_deref().unwrap_or_default().to_string();
+    [
+        (Field::Name, def.name.to_string()),
+        (Field::Kind, def.kind.as_str().to_string()),
+        (Field::Scope, def.scope.as_str().to_string()),
+        (Field::RetryMode, def.retry_mode.as_str().to_string()),
+        (
+            Field::PassThroughMode,
+            def.pass_through_mode.as_str().to_string(),
+        ),
+        (Field::Level, def.level.to_string()),
+        (Field::MappingCode, def.mapping_code.to_string()),
+        (Field::Message, def.message.to_string()),
+        (Field::I18n, optional(&def.i18n)),
+        (Field::Description, optional(&def.description)),
+        (Field::Deprecated, optional(&def.deprecated)),
+    ]
+}
+
+/// Compares two versions of a catalog, matching namespaces and errors by
+/// code. Namespace changes come first.
+pub fn diff(old: &Catalog, new: &Catalog) -> CatalogDiff {
+    let mut changes = Vec::new();
+    let old_names: BTreeMap<Namespace, &str> = old
+        .namespaces
+        .iter()
+        .map(|ns| (ns.namespace, ns.name.as_str()))
+        .collect();
+    let new_names: BTreeMap<Namespace, &str> = new
+        .namespaces
+        .iter()
+        .map(|ns| (ns.namespace, ns.name.as_str()))
+        .collect();
+    let namespaces: BTreeSet<Namespace> =
+        old_names.keys().chain(new_names.keys()).copied().collect();
+    for namespace in namespaces {
+        match (old_names.get(&namespace), new_names.get(&namespace)) {
+            (Some(old), None) => changes.push(Change::NamespaceRemoved {
+                namespace,
+                name: old.to_string(),
+            }),
+            (None, Some(new)) => changes.push(Change::NamespaceAdded {
+                namespace,
+                name: new.to_string(),
+            }),
+            (Some(old), Some(new)) if old != new => changes.push(Change::NamespaceRenamed {
+                namespace,
+                old: old.to_string(),
+                new: new.to_string(),
+            }),
+            _ => {}
+        }
+    }
+
+    let old_defs: BTreeMap<ErrorCode, &ErrorDef> =
+        old.errors().map(|def| (def.code, def)).collect();
+    let new_defs: BTreeMap<ErrorCode, &ErrorDef> =
+        new.errors().map(|def| (def.code, def)).collect();
+    let retired = |catalog: &Catalog| -> BTreeSet<ErrorCode> {
+        catalog
+            .namespaces
+            .iter()
+            .flat_map(|ns| ns.retired.iter().copied())
+            .collect()
+    };
+    let (retired, new_retired) = (retired(old), retired(new));
+    let codes: BTreeSet<ErrorCode> = old_defs
+        .keys()
+        .chain(new_defs.keys())
+        .chain(&retired)
+        .copied()
+        .collect();
+
+    for code in codes {
+        match (old_defs.get(&code), new_defs.get(&code)) {
+            (Some(old), None) => changes.push(Change::Removed {
+                code,
+                name: old.name.to_string(),
+            }),
+            (None, Some(new)) if retired.contains(&code) => changes.push(Change::Reused {
+                code,
+                name: new.name.to_string(),
+            }),
+            (None, Some(new)) => changes.push(Change::Added {
+                code,
+                name: new.name.to_string(),
+            }),
+            (Some(old), Some(new)) => {
+                for ((field, old), (_, new_value)) in fields(old).into_iter().zip(fields(new)) {
+                    if old != new_value {
+                        changes.push(Change::Changed {
+                            code,
+                            name: new.name.to_string(),
+                            field,
+                            old,
+                            new: new_value,
+                        });
+                    }
+                }
+            }
+            (None, None) if !new_retired.contains(&code) => {
+                changes.push(Change::Unretired { code })
+            }
+            (None, None) => {}
+        }
+    }
+    CatalogDiff { changes }
+}
@@ -0,0 +1,425 @@
+//! A static reference of the errors of a catalog for API consumers: an index
+//! and a page per namespace, in HTML or Markdown.
+//!
+//! Every error is anchored by its name and by its code, and the page of a
+//! namespace is `{namespace}/index.html`. Publishing the site at the
+//! `type_base` of a [`ProblemConfig`] therefore makes problem type URIs and
+//! [`ProblemConfig::help`] links, `{type_base}/{namespace}/#{name}`, land on
+//! the error.
+//!
+//! ```no_run
+//! use widerror::catalog::docs::DocSite;
+//! use widerror::catalog::Catalog;
+//! use widerror::problem::ProblemConfig;
+//!
+//! let catalog = Catalog::load("errors.toml").unwrap();
+//! let site = DocSite {
+//!     problem: ProblemConfig::new("https://errors.example.com"),
+//!     ..DocSite::default()
+//! };
+//! site.write(&catalog, None, "target/errors").unwrap();
+//! ```
+//!
+//! An error shows its kind, HTTP status, retry guidance, message, deprecation
+//! note and, for `locales` and errors with an i18n key, the message of each
+//! locale.
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::{Catalog, NamespaceDef};
+use crate::i18n::MessageCatalog;
+use crate::problem::ProblemConfig;
+use crate::registry::ErrorDef;
+use crate::retry::RetryPolicy;
+use crate::RetryMode;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DocFormat {
+    #[default]
+    Html,
+    Markdown,
+}
+
+impl DocFormat {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            DocFormat::Html => "html",
+            DocFormat::Markdown => "md",
+        }
+    }
+}
+
+/// How the reference is rendered.
+#[derive(Debug, Clone)]
+pub struct DocSite {
+    pub format: DocFormat,
+    /// Heading of the index, and suffix of page titles.
+    pub title: String,
+    /// Gives the problem type URI shown with each error; `type_base` is where
+    /// the site is published.
+    pub problem: ProblemConfig,
+    /// Locales whose messages are listed, in this order.
+    pub locales: Vec<String>,
+    /// Decides the guidance of errors whose retry mode is `Unknown`.
+    pub retry: RetryPolicy,
+}
+
+impl Default for DocSite {
+    fn default() -> Self {
+        DocSite {
+            format: DocFormat::Html,
+            title: "Error reference".to_string(),
+            problem: ProblemConfig::default(),
+            locales: Vec::new(),
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// A file of the site. `path` is relative to the root of the site and
+/// separated by `/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub path: String,
+    pub content: String,
+}
+
+/// What is shown of one error, before formatting.
+struct Entry<'a> {
+    def: &'a ErrorDef,
+    /// label, value, whether the value is code
+    rows: Vec<(&'static str, String, bool)>,
+    type_uri: Option<String>,
+    /// locale, message
+    messages: Vec<(&'a str, Cow<'a, str>)>,
+}
+
+impl DocSite {
+    /// The index followed by the page of each namespace, in code order.
+    /// `messages` resolves the i18n keys of errors for `locales`.
+    pub fn pages(&self, catalog: &Catalog, messages: Option<&dyn MessageCatalog>) -> Vec<Page> {
+        let mut namespaces: Vec<&NamespaceDef> = catalog.namespaces.iter().collect();
+        namespaces.sort_by_key(|ns| ns.namespace);
+        let extension = self.format.extension();
+        let mut pages = vec![Page {
+            path: format!("index.{}", extension),
+            content: match self.format {
+                DocFormat::Html => self.html_index(&namespaces),
+                DocFormat::Markdown => self.markdown_index(&namespaces),
+            },
+        }];
+        for ns in namespaces {
+            let mut defs: Vec<&ErrorDef> = ns.errors.iter().collect();
+            defs.sort_by_key(|def| def.code);
+            let entries: Vec<Entry> = defs
+                .into_iter()
+                .map(|def| self.entry(def, messages))
+                .collect();
+            pages.push(Page {
+                path: format!("{}/index.{}", ns.namespace, extension),
+                content: match self.format {
+                    DocFormat::Html => self.html_namespace(ns, &entries),
+                    DocFormat::Markdown => self.markdown_namespace(ns, &entries),
+                },
+            });
+        }
+        pages
+    }
+
+    /// Writes the pages under `dir`, creating directories as needed, and
+    /// returns the written files.
+    pub fn write<P: AsRef<Path>>(
+        &self,
+        catalog: &Catalog,
+        messages: Option<&dyn MessageCatalog>,
+        dir: P,
+    ) -> std::io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let mut written = Vec::new();
+        for page in self.pages(catalog, messages) {
+            let path = dir.join(&page.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, page.content)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    fn entry<'a>(
+        &'a self,
+        def: &'a ErrorDef,
+        messages: Option<&'a dyn MessageCatalog>,
+    ) -> Entry<'a> {
+        let mut rows = vec![
+            ("Kind", def.kind.as_str().to_string(), true),
+            ("HTTP status", def.kind.http_status().to_string(), false),
+            ("Retry", self.retry_guidance(def).to_string(), false),
+            ("Message", def.message.to_string(), true),
+        ];
+        if let Some(key) = &def.i18n {
+            rows.push(("i18n key", key.to_string(), true));
+        }
+        let localized = match (&def.i18n, messages) {
+            (Some(key), Some(messages)) => self
+                .locales
+                .iter()
+                .filter_map(|locale| {
+                    let text = messages.resolve(locale, key)?;
+                    Some((locale.as_str(), text))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Entry {
+            def,
+            rows,
+            type_uri: self.problem.doc_url(def.namespace, &def.name),
+            messages: localized,
+        }
+    }
+
+    fn retry_guidance(&self, def: &ErrorDef) -> &'static str {
+        match def.retry_mode {
+            RetryMode::Allowed => "Safe to retry.",
+            RetryMode::Denied => "Do not retry; the same request fails again.",
+            RetryMode::Unknown if self.retry.retryable_kinds.contains(&def.kind) => {
+                "Retry with backoff; this kind of error is usually transient."
+            }
+            RetryMode::Unknown => "Not retried by default.",
+        }
+    }
+
+    fn page_title(&self, ns: &NamespaceDef) -> String {
+        format!("{} ({})", ns.name, ns.namespace)
+    }
+
+    fn html_index(&self, namespaces: &[&NamespaceDef]) -> String {
+        let mut out = html_head(&self.title);
+        let _ = writeln!(out, "<h1>{}</h1>", html(&self.title));
+        out.push_str("<table>\n<tr><th>Namespace</th><th>Name</th><th>Errors</th><th>Description</th></tr>\n");
+        for ns in namespaces {
+            let _ = writeln!(
+                out,
+                "<tr><td><a href=\"{0}/index.html\"><code>{0}</code></a></td><td>{1}</td><td>{2}</td><td>{3}</td></tr>",
+                ns.namespace,
+                html(&ns.name),
+                ns.errors.len(),
+                html(ns.description.as_deref().unwrap_or_default()),
+            );
+        }
+        out.push_str("</table>\n</body>\n</html>\n");
+        out
+    }
+
+    fn html_namespace(&self, ns: &NamespaceDef, entries: &[Entry]) -> String {
+        let title = self.page_title(ns);
+        let mut out = html_head(&format!("{} · {}", title, self.title));
+        let _ = writeln!(
+            out,
+            "<nav><a href=\"../index.html\">{}</a></nav>",
+            html(&self.title)
+        );
+        let _ = writeln!(out, "<h1>{}</h1>", html(&title));
+        if let Some(description) = &ns.description {
+            html_paragraphs(&mut out, description);
+        }
+        out.push_str("<ul>\n");
+        for entry in entries {
+            let _ = writeln!(
+                out,
+                "<li><a href=\"#{0}\"><code>{1}</code> {0}</a>{2}</li>",
+                html(&entry.def.name),
+                entry.def.code,
+                if entry.def.deprecated.is_some() {
+                    " (deprecated)"
+                } else {
+                    ""
+                },
+            );
+        }
+        out.push_str("</ul>\n");
+        for entry in entries {
+            let def = entry.def;
+            let _ = writeln!(out, "<section id=\"{}\">", html(&def.name));
+            let _ = writeln!(
+                out,
+                "<h2 id=\"{1}\"><a href=\"#{0}\">{0}</a> <code>{1}</code></h2>",
+                html(&def.name),
+                def.code
+            );
+            if let Some(note) = &def.deprecated {
+                let _ = writeln!(
+                    out,
+                    "<p class=\"deprecated\"><strong>Deprecated:</strong> {}</p>",
+                    html(note)
+                );
+            }
+            if let Some(description) = &def.description {
+                html_paragraphs(&mut out, description);
+            }
+            out.push_str("<table>\n");
+            for (label, value, code) in &entry.rows {
+                let value = if *code {
+                    format!("<code>{}</code>", html(value))
+                } else {
+                    html(value)
+                };
+                let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", label, value);
+            }
+            if let Some(uri) = &entry.type_uri {
+                let _ = writeln!(
+                    out,
+                    "<tr><th>Problem type</th><td><a href=\"{0}\"><code>{0}</code></a></td></tr>",
+                    html(uri)
+                );
+            }
+            out.push_str("</table>\n");
+            if !entry.messages.is_empty() {
+                out.push_str("<table>\n<tr><th>Locale</th><th>Message</th></tr>\n");
+                for (locale, text) in &entry.messages {
+                    let _ = writeln!(
+                        out,
+                        "<tr><td>{}</td><td>{}</td></tr>",
+                        html(locale),
+                        html(text)
+                    );
+                }
+                out.push_str("</table>\n");
+            }
+            out.push_str("</section>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn markdown_index(&self, namespaces: &[&NamespaceDef]) -> String {
+        let mut out = format!("# {}\n\n", self.title);
+        out.push_str("| Namespace | Name | Errors | Description |\n|---|---|---|---|\n");
+        for ns in namespaces {
+            let _ = writeln!(
+                out,
+                "| [`{0}`]({0}/index.md) | {1} | {2} | {3} |",
+                ns.namespace,
+                cell(&ns.name),
+                ns.errors.len(),
+                cell(ns.description.as_deref().unwrap_or_default()),
+            );
+        }
+        out
+    }
+
+    fn markdown_namespace(&self, ns: &NamespaceDef, entries: &[Entry]) -> String {
+        let mut out = format!("[{}](../index.md)\n\n", self.title);
+        let _ = writeln!(out, "# {}\n", self.page_title(ns));
+        if let Some(description) = &ns.description {
+            let _ = writeln!(out, "{}\n", description.trim_end());
+        }
+        for entry in entries {
+            let _ = writeln!(
+                out,
+                "- [`{1}` {0}](#{0}){2}",
+                entry.def.name,
+                entry.def.code,
+                if entry.def.deprecated.is_some() {
+                    " (deprecated)"
+                } else {
+                    ""
+                },
+            );
+        }
+        for entry in entries {
+            let def = entry.def;
+            let _ = writeln!(
+                out,
+                "\n<a id=\"{0}\"></a><a id=\"{1}\"></a>\n\n## {0} `{1}`\n",
+                def.name, def.code
+            );
+            if let Some(note) = &def.deprecated {
+                let _ = writeln!(out, "> **Deprecated:** {}\n", note.trim_end());
+            }
+            if let Some(description) = &def.description {
+                let _ = writeln!(out, "{}\n", description.trim_end());
+            }
+            out.push_str("| | |\n|---|---|\n");
+            for (label, value, code) in &entry.rows {
+                let value = if *code {
+                    code_span(value)
+                } else {
+                    value.clone()
+                };
+                let _ = writeln!(out, "| {} | {} |", label, cell(&value));
+            }
+            if let Some(uri) = &entry.type_uri {
+                let _ = writeln!(out, "| Problem type | <{}> |", uri);
+            }
+            if !entry.messages.is_empty() {
+                out.push_str("\n| Locale | Message |\n|---|---|\n");
+                for (locale, text) in &entry.messages {
+                    let _ = writeln!(out, "| {} | {} |", cell(locale), cell(text));
+                }
+            }
+        }
+        out
+    }
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:56rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+table{border-collapse:collapse;margin:1rem 0}th,td{border:1px solid #ddd;padding:.25rem .5rem;text-align:left}\
+section{margin-top:2.5rem}h2 a{color:inherit;text-decoration:none}.deprecated{background:#fff4e5;padding:.5rem}";
+
+fn html_head(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        html(title),
+        STYLE
+    )
+}
+
+/// One `<p>` per blank-line separated paragraph.
+fn html_paragraphs(out: &mut String, text: &str) {
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        let _ = writeln!(out, "<p>{}</p>", html(paragraph));
+    }
+}
+
+fn html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A Markdown table cell: one line, `|` escaped.
+fn cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// A Markdown code span with a fence longer than any backtick run of `text`.
+fn code_span(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        format!("{0} {1} {0}", fence, text)
+    } else {
+        format!("{0}{1}{0}", fence, text)
+    }
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+use std::iter::FusedIterator;
+
+use crate::{ErrorCode, Kind, WidError};
+
+/// Iterator over an error and its sources, outermost first.
+#[derive(Clone)]
+pub struct Chain<'a> {
+    next: Option<&'a (dyn Error + 'static)>,
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a (dyn Error + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = current.source();
+        Some(current)
+    }
+}
+
+impl FusedIterator for Chain<'_> {}
+
+impl WidError {
+    /// Every layer, starting with `self`, including foreign errors.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain { next: Some(self) }
+    }
+
+    /// The innermost layer.
+    pub fn root_cause(&self) -> &(dyn Error + 'static) {
+        self.chain().last().unwrap_or(self)
+    }
+
+    /// The direct source when it is a `WidError`.
+    pub fn source_wid(&self) -> Option<&WidError> {
+        self.source()?.downcast_ref()
+    }
+
+    /// The `WidError` layers, starting with `self`; layers below a foreign
+    /// error are found too.
+    pub fn wid_chain(&self) -> impl Iterator<Item = &WidError> {
+        self.chain().filter_map(|e| e.downcast_ref())
+    }
+
+    /// The first `WidError` layer matching `predicate`.
+    pub fn find<P: FnMut(&WidError) -> bool>(&self, mut predicate: P) -> Option<&WidError> {
+        self.wid_chain().find(|e| predicate(e))
+    }
+
+    /// Whether any `WidError` layer has `kind`.
+    pub fn any_kind(&self, kind: Kind) -> bool {
+        self.wid_chain().any(|e| e.kind == kind)
+    }
+
+    /// Whether any `WidError` layer has `code`.
+    pub fn contains_code(&self, code: ErrorCode) -> bool {
+        self.wid_chain().any(|e| e.code == code)
+    }
+}
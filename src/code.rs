@@ -0,0 +1,240 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// A 9-digit error code [100000000, 999999999], or [`ErrorCode::NONE`].
+///
+/// The first 5 digits are the [`Namespace`], the last 4 the local sequence.
+/// Constructing an invalid code in a `const` fails to compile:
+///
+/// ```compile_fail
+/// const BAD: widerror::ErrorCode = widerror::ErrorCode::new(1234);
+/// ```
+///
+/// On the wire it is a plain number.
+#[derive(
+    Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default,
+)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct ErrorCode(u32);
+
+/// A 5-digit namespace [10000, 99999], or [`Namespace::NONE`].
+///
+/// On the wire it is a plain number.
+#[derive(
+    Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default,
+)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct Namespace(u32);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    /// not 9 digits
+    InvalidCode(u32),
+    /// not 5 digits
+    InvalidNamespace(u32),
+    /// local sequence above 9999
+    InvalidSequence(u32),
+    /// the code does not start with the namespace
+    NamespaceMismatch { code: u32, namespace: u32 },
+}
+
+impl ErrorCode {
+    /// The unset code, used by `WidError::default()`.
+    pub const NONE: ErrorCode = ErrorCode(0);
+    pub const MIN: u32 = 100_000_000;
+    pub const MAX: u32 = 999_999_999;
+
+    /// Panics (or fails to compile in `const` context) if `code` is invalid.
+    pub const fn new(code: u32) -> ErrorCode {
+        match ErrorCode::try_new(code) {
+            Ok(code) => code,
+            Err(_) => panic!("error code must have 9 digits"),
+        }
+    }
+
+    pub const fn try_new(code: u32) -> Result<ErrorCode, CodeError> {
+        if code == 0 || (code >= ErrorCode::MIN && code <= ErrorCode::MAX) {
+            Ok(ErrorCode(code))
+        } else {
+            Err(CodeError::InvalidCode(code))
+        }
+    }
+
+    /// Panics (or fails to compile in `const` context) if `code` is invalid or
+    /// does not start with `namespace`.
+    pub const fn with_namespace(code: u32, namespace: Namespace) -> ErrorCode {
+        match ErrorCode::try_with_namespace(code, namespace) {
+            Ok(code) => code,
+            Err(CodeError::NamespaceMismatch { .. }) => {
+                panic!("error code must start with its namespace")
+            }
+            Err(_) => panic!("error code must have 9 digits"),
+        }
+    }
+
+    pub const fn try_with_namespace(
+        code: u32,
+        namespace: Namespace,
+    ) -> Result<ErrorCode, CodeError> {
+        match ErrorCode::try_new(code) {
+            Ok(c) if c.namespace().0 == namespace.0 => Ok(c),
+            Ok(_) => Err(CodeError::NamespaceMismatch {
+                code,
+                namespace: namespace.0,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Composes `namespace * 10000 + sequence`; panics if `sequence > 9999`.
+    pub const fn from_parts(namespace: Namespace, sequence: u16) -> ErrorCode {
+        match ErrorCode::try_from_parts(namespace, sequence) {
+            Ok(code) => code,
+            Err(_) => panic!("error code needs a namespace and a sequence of at most 4 digits"),
+        }
+    }
+
+    pub const fn try_from_parts(
+        namespace: Namespace,
+        sequence: u16,
+    ) -> Result<ErrorCode, CodeError> {
+        if namespace.is_none() {
+            Err(CodeError::InvalidNamespace(0))
+        } else if sequence > 9999 {
+            Err(CodeError::InvalidSequence(sequence as u32))
+        } else {
+            Ok(ErrorCode(namespace.0 * 10_000 + sequence as u32))
+        }
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    pub const fn is_none(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The first 5 digits.
+    pub const fn namespace(self) -> Namespace {
+        Namespace(self.0 / 10_000)
+    }
+
+    /// The last 4 digits.
+    pub const fn sequence(self) -> u16 {
+        (self.0 % 10_000) as u16
+    }
+}
+
+impl Namespace {
+    /// The unset namespace, used by `WidError::default()`.
+    pub const NONE: Namespace = Namespace(0);
+    pub const MIN: u32 = 10_000;
+    pub const MAX: u32 = 99_999;
+
+    /// Panics (or fails to compile in `const` context) if `namespace` is invalid.
+    pub const fn new(namespace: u32) -> Namespace {
+        match Namespace::try_new(namespace) {
+            Ok(namespace) => namespace,
+            Err(_) => panic!("namespace must have 5 digits"),
+        }
+    }
+
+    pub const fn try_new(namespace: u32) -> Result<Namespace, CodeError> {
+        if namespace == 0 || (namespace >= Namespace::MIN && namespace <= Namespace::MAX) {
+            Ok(Namespace(namespace))
+        } else {
+            Err(CodeError::InvalidNamespace(namespace))
+        }
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    pub const fn is_none(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether `code` starts with this namespace.
+    pub const fn contains(self, code: ErrorCode) -> bool {
+        !self.is_none() && code.namespace().0 == self.0
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Display for Namespace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl From<ErrorCode> for u32 {
+    fn from(code: ErrorCode) -> Self {
+        code.0
+    }
+}
+
+impl From<Namespace> for u32 {
+    fn from(namespace: Namespace) -> Self {
+        namespace.0
+    }
+}
+
+impl TryFrom<u32> for ErrorCode {
+    type Error = CodeError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        ErrorCode::try_new(code)
+    }
+}
+
+impl TryFrom<u32> for Namespace {
+    type Error = CodeError;
+
+    fn try_from(namespace: u32) -> Result<Self, Self::Error> {
+        Namespace::try_new(namespace)
+    }
+}
+
+impl PartialEq<u32> for ErrorCode {
+    fn eq(&self, other: &u32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<u32> for Namespace {
+    fn eq(&self, other: &u32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Display for CodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeError::InvalidCode(code) => write!(f, "error code {} is not 9 digits", code),
+            CodeError::InvalidNamespace(namespace) => {
+                write!(f, "namespace {} is not 5 digits", namespace)
+            }
+            CodeError::InvalidSequence(sequence) => {
+                write!(f, "sequence {} is more than 4 digits", sequence)
+            }
+            CodeError::NamespaceMismatch { code, namespace } => {
+                write!(
+                    f,
+                    "error code {} does not start with its namespace {}",
+                    code, namespace
+                )
+            }
+        }
+    }
+}
+
+impl Error for CodeError {}
@@ -0,0 +1,317 @@
+//! Machine-readable error details modeled on
+//! https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+//!
+//! Each payload type implements [`ErrorDetail`] and is stored in
+//! [`WidError::details`](crate::WidError::details) tagged with its type URL,
+//! using the protobuf JSON mapping:
+//!
+//! ```json
+//! [{"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "1.500s"}]
+//! ```
+//!
+//! Custom payloads only need a type URL:
+//!
+//! ```
+//! use widerror::details::ErrorDetail;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Balance {
+//!     missing_cents: u64,
+//! }
+//!
+//! impl ErrorDetail for Balance {
+//!     const TYPE_URL: &'static str = "type.example.com/billing.Balance";
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::WidError;
+
+/// A payload that can be attached to a `WidError`.
+pub trait ErrorDetail: Serialize + DeserializeOwned {
+    /// Identifies the payload type on the wire.
+    const TYPE_URL: &'static str;
+}
+
+/// One type-tagged payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detail {
+    pub type_url: String,
+    /// The payload fields; payloads that do not serialize to an object are
+    /// kept under `value`.
+    pub fields: Map<String, Value>,
+}
+
+impl Detail {
+    pub fn new<T: ErrorDetail>(detail: &T) -> Detail {
+        let fields = match serde_json::to_value(detail) {
+            Ok(Value::Object(fields)) => fields,
+            Ok(other) => Map::from_iter([("value".to_string(), other)]),
+            Err(_) => Map::new(),
+        };
+        Detail {
+            type_url: T::TYPE_URL.to_string(),
+            fields,
+        }
+    }
+
+    pub fn is<T: ErrorDetail>(&self) -> bool {
+        self.type_url == T::TYPE_URL
+    }
+
+    /// Decodes the payload if it has type `T`.
+    pub fn to<T: ErrorDetail>(&self) -> Option<T> {
+        if !self.is::<T>() {
+            return None;
+        }
+        serde_json::from_value(Value::Object(self.fields.clone()))
+            .ok()
+            .or_else(|| serde_json::from_value(self.fields.get("value")?.clone()).ok())
+    }
+}
+
+impl Serialize for Detail {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = Map::with_capacity(self.fields.len() + 1);
+        map.insert("@type".to_string(), Value::String(self.type_url.clone()));
+        map.extend(self.fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+        map.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Detail {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = Map::deserialize(deserializer)?;
+        match fields.remove("@type") {
+            Some(Value::String(type_url)) => Ok(Detail { type_url, fields }),
+            _ => Err(D::Error::missing_field("@type")),
+        }
+    }
+}
+
+/// The details of one error, in insertion order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct Details(Vec<Detail>);
+
+impl Details {
+    pub fn push<T: ErrorDetail>(&mut self, detail: &T) {
+        self.0.push(Detail::new(detail));
+    }
+
+    /// The first payload of type `T`.
+    pub fn get<T: ErrorDetail>(&self) -> Option<T> {
+        self.0.iter().find_map(Detail::to)
+    }
+
+    /// Every payload of type `T`.
+    pub fn get_all<T: ErrorDetail>(&self) -> Vec<T> {
+        self.0.iter().filter_map(Detail::to).collect()
+    }
+
+    /// Drops the payloads for which `keep` returns false.
+    pub fn retain<F: FnMut(&Detail) -> bool>(&mut self, keep: F) {
+        self.0.retain(keep)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Detail> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Detail> {
+        self.0.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl WidError {
+    pub fn with_detail<T: ErrorDetail>(mut self, detail: T) -> WidError {
+        self.details.push(&detail);
+        self
+    }
+
+    /// The first detail of type `T`, e.g. `err.detail::<RetryInfo>()`.
+    pub fn detail<T: ErrorDetail>(&self) -> Option<T> {
+        self.details.get()
+    }
+}
+
+macro_rules! google_rpc {
+    ($($ty:ident),*) => {
+        $(impl ErrorDetail for $ty {
+            const TYPE_URL: &'static str = concat!("type.googleapis.com/google.rpc.", stringify!($ty));
+        })*
+    };
+}
+
+google_rpc!(
+    ErrorInfo,
+    RetryInfo,
+    DebugInfo,
+    QuotaFailure,
+    PreconditionFailure,
+    BadRequest,
+    RequestInfo,
+    ResourceInfo,
+    Help,
+    LocalizedMessage
+);
+
+/// The reason of the error with structured metadata.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ErrorInfo {
+    pub reason: String,
+    pub domain: String,
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// When the client may retry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryInfo {
+    #[serde(with = "duration")]
+    pub retry_delay: Duration,
+}
+
+/// Debugging information for internal use.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DebugInfo {
+    pub stack_entries: Vec<String>,
+    pub detail: String,
+}
+
+/// Which quotas were exceeded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuotaFailure {
+    pub violations: Vec<QuotaViolation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuotaViolation {
+    pub subject: String,
+    pub description: String,
+}
+
+/// Which preconditions failed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PreconditionFailure {
+    pub violations: Vec<PreconditionViolation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PreconditionViolation {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub subject: String,
+    pub description: String,
+}
+
+/// Which request fields were invalid.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BadRequest {
+    pub field_violations: Vec<FieldViolation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
+}
+
+/// Identifies the request, e.g. for a support ticket.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RequestInfo {
+    pub request_id: String,
+    pub serving_data: String,
+}
+
+/// The resource being accessed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceInfo {
+    pub resource_type: String,
+    pub resource_name: String,
+    pub owner: String,
+    pub description: String,
+}
+
+/// Links to documentation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Help {
+    pub links: Vec<Link>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Link {
+    pub description: String,
+    pub url: String,
+}
+
+/// A message localized for the caller.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalizedMessage {
+    pub locale: String,
+    pub message: String,
+}
+
+/// `google.protobuf.Duration` JSON form: `"1.500s"`.
+mod duration {
+    use std::time::Duration;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        let nanos = d.subsec_nanos();
+        let text = if nanos == 0 {
+            format!("{}s", d.as_secs())
+        } else if nanos.is_multiple_of(1_000_000) {
+            format!("{}.{:03}s", d.as_secs(), nanos / 1_000_000)
+        } else if nanos.is_multiple_of(1_000) {
+            format!("{}.{:06}s", d.as_secs(), nanos / 1_000)
+        } else {
+            format!("{}.{:09}s", d.as_secs(), nanos)
+        };
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let invalid = || D::Error::custom(format!("invalid duration `{}`", text));
+        let number = text.strip_suffix('s').ok_or_else(invalid)?;
+        let (secs, frac) = number.split_once('.').unwrap_or((number, ""));
+        let secs = secs.parse::<u64>().map_err(|_| invalid())?;
+        if frac.len() > 9 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let nanos = format!("{:0<9}", frac)
+            .parse::<u32>()
+            .map_err(|_| invalid())?;
+        Ok(Duration::new(secs, nanos))
+    }
+}
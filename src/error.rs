@@ -1,61 +1,122 @@
+use std::backtrace::Backtrace;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
+use std::panic::Location;
+use std::sync::Arc;
 
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+use crate::capture::capture_backtrace;
+use crate::details::Details;
+use crate::source::Source;
+use crate::{CodeError, ErrorCode, Message, Namespace};
+
+/// Deserializing fails when `namespace` is set and `code` does not start with
+/// it; an unset namespace is taken from `code`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(remote = "Self")]
 pub struct WidError {
     /// error message
     pub message: Message,
     /// error code
-    /// suggest digits [100000000, 999999999]
-    pub code: u32,
+    /// digits [100000000, 999999999]
+    pub code: ErrorCode,
     /// error name
     pub name: String,
-    /// suggest as a prefix of code, 5 digits [10000, 99999]
-    pub namespace: u32,
+    /// the prefix of code, 5 digits [10000, 99999]
+    pub namespace: Namespace,
     pub kind: Kind,
     pub scope: Scope,
-    /// error level [0, 255]
-    /// via: https://www.yisu.com/zixun/404282.html
+    /// error level [0, 255], banded by [`Severity`](crate::Severity)
     pub level: u8,
     pub retry_mode: RetryMode,
     pub pass_through_mode: PassThroughMode,
     pub mapping_code: i64,
-    source_error: Option<Box<WidError>>,
+    /// machine-readable payloads, see [`details`](crate::details)
+    #[serde(default, skip_serializing_if = "Details::is_empty")]
+    pub details: Details,
+    pub(crate) source_error: Option<Source>,
+    #[serde(skip)]
+    pub(crate) location: Option<&'static Location<'static>>,
+    #[serde(skip)]
+    pub(crate) backtrace: Option<Arc<Backtrace>>,
+    #[cfg(feature = "tracing")]
+    #[serde(skip)]
+    pub(crate) span_trace: Option<tracing_error::SpanTrace>,
 }
 
 impl WidError {
-    pub fn new(code: u32, message: Message) -> WidError {
+    /// The namespace is taken from the prefix of `code`.
+    #[track_caller]
+    pub fn new(code: ErrorCode, message: Message) -> WidError {
         WidError {
             code,
+            namespace: code.namespace(),
             message,
+            location: Some(Location::caller()),
+            backtrace: capture_backtrace(),
+            #[cfg(feature = "tracing")]
+            span_trace: crate::trace::capture_span_trace(),
             ..WidError::default()
         }
     }
-    pub fn with_source(mut self, e: WidError) -> WidError {
-        self.source_error = Some(Box::new(e));
+    /// Any error can be a source. A `WidError` source is serialized as is,
+    /// others as a [`ForeignError`](crate::ForeignError) snapshot, while
+    /// [`Error::source`] still returns the original object.
+    ///
+    /// Records the call site and traces unless the error already has them.
+    #[track_caller]
+    pub fn with_source<E: Error + Send + Sync + 'static>(mut self, e: E) -> WidError {
+        self.source_error = Some(Source::new(e));
+        self.location.get_or_insert(Location::caller());
+        if self.backtrace.is_none() {
+            self.backtrace = capture_backtrace();
+        }
+        #[cfg(feature = "tracing")]
+        if self.span_trace.is_none() {
+            self.span_trace = crate::trace::capture_span_trace();
+        }
         self
     }
 }
 
-impl Display for WidError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f,
-               "code={}, name={}, namespace={}, scope={}, kind={}, level={}, message={}, retry_mode={}, pass_through_mode={}, mapping_code={}, source_error=({})",
-               self.code, self.name, self.namespace, self.scope, self.kind, self.level, self.message, self.retry_mode, self.pass_through_mode, self.mapping_code,
-               if self.source_error.is_some() { format!("{}", self.source_error.as_ref().unwrap()) } else { String::new() }
-        )
+impl Serialize for WidError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WidError::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WidError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut err = WidError::deserialize(deserializer)?;
+        if !err.code.is_none() {
+            if err.namespace.is_none() {
+                err.namespace = err.code.namespace();
+            } else if !err.namespace.contains(err.code) {
+                return Err(D::Error::custom(CodeError::NamespaceMismatch {
+                    code: err.code.get(),
+                    namespace: err.namespace.get(),
+                }));
+            }
+        }
+        Ok(err)
     }
 }
 
 impl Error for WidError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        if let Some(ref e) = self.source_error {
-            Some(e as &dyn Error)
-        } else {
-            None
+        self.source_error.as_ref().map(Source::as_error)
+    }
+
+    #[cfg(widerror_nightly)]
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        if let Some(backtrace) = &self.backtrace {
+            request.provide_ref::<Backtrace>(backtrace);
+        }
+        if let Some(location) = self.location {
+            request.provide_ref::<Location<'static>>(location);
         }
     }
 }
@@ -68,12 +129,13 @@ impl Error for WidError {
 /// the most specific error code that applies.  For example, prefer
 /// `OutOfRange` over `FailedPrecondition` if both codes apply.
 /// Similarly prefer `NotFound` or `AlreadyExists` over `FailedPrecondition`.
-#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Copy, Clone)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash, Debug, Copy, Clone, Default)]
 #[repr(i8)]
 pub enum Kind {
     /// Not an error; returned on success
     ///
     /// HTTP Mapping: 200 Ok
+    #[default]
     Ok = 0,
 
     /// The operation was cancelled, typically by the caller.
@@ -226,93 +288,74 @@ pub enum Kind {
     DataLoss = 15,
 }
 
-impl Default for Kind {
-    fn default() -> Self {
-        Self::Ok
-    }
-}
-
+/// `{}` prints the canonical name, `{:#}` the number.
 impl Display for Kind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.clone() as i8)
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum Message {
-    Default(String),
-    I18n(String),
-}
-
-impl Default for Message {
-    fn default() -> Self {
-        Self::Default(String::new())
-    }
-}
-
-impl Display for Message {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        if f.alternate() {
+            write!(f, "{}", *self as i8)
+        } else {
+            f.write_str(self.as_str())
+        }
     }
 }
 
-#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Copy, Clone)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash, Debug, Copy, Clone, Default)]
 #[repr(i8)]
 pub enum Scope {
+    #[default]
     Internal = 0,
     Clientside = 1,
     Serverside = 2,
 }
 
-impl Default for Scope {
-    fn default() -> Self {
-        Self::Internal
-    }
-}
-
+/// `{}` prints the canonical name, `{:#}` the number.
 impl Display for Scope {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.clone() as i8)
+        if f.alternate() {
+            write!(f, "{}", *self as i8)
+        } else {
+            f.write_str(self.as_str())
+        }
     }
 }
 
-#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Copy, Clone)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash, Debug, Copy, Clone, Default)]
 #[repr(i8)]
 pub enum RetryMode {
+    #[default]
     Unknown = 0,
     Allowed = 1,
     Denied = 2,
 }
 
-impl Default for RetryMode {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
+/// `{}` prints the canonical name, `{:#}` the number.
 impl Display for RetryMode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.clone() as i8)
+        if f.alternate() {
+            write!(f, "{}", *self as i8)
+        } else {
+            f.write_str(self.as_str())
+        }
     }
 }
 
-#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Copy, Clone)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash, Debug, Copy, Clone, Default)]
 #[repr(i8)]
 pub enum PassThroughMode {
+    #[default]
     Auto = 0,
     Should = 1,
     Never = 2,
 }
 
-impl Default for PassThroughMode {
-    fn default() -> Self {
-        Self::Auto
-    }
-}
-
+/// `{}` prints the canonical name, `{:#}` the number.
 impl Display for PassThroughMode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.clone() as i8)
+        if f.alternate() {
+            write!(f, "{}", *self as i8)
+        } else {
+            f.write_str(self.as_str())
+        }
     }
 }
 
@@ -0,0 +1,96 @@
+//! Conversions between `WidError` and `tonic::Status`.
+//!
+//! The error travels in `grpc-status-details-bin` as a `google.rpc.Status`
+//! holding one `google.rpc.ErrorInfo`: `reason` is the error name, `domain`
+//! the namespace, and `metadata` carries every field individually plus the
+//! complete serialized error (source chain included) under `widerror`, so a
+//! Rust peer can rebuild it losslessly.
+
+use std::collections::HashMap;
+
+use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+
+use crate::{ErrorCode, Kind, Message, Namespace, PassThroughMode, RetryMode, Scope, WidError};
+
+/// `ErrorInfo.metadata` key holding the serialized `WidError`.
+pub const PAYLOAD_KEY: &str = "widerror";
+
+impl From<WidError> for Status {
+    fn from(err: WidError) -> Self {
+        let reason = if err.name.is_empty() {
+            err.code.to_string()
+        } else {
+            err.name.clone()
+        };
+        let mut metadata = HashMap::new();
+        metadata.insert("code".to_string(), err.code.to_string());
+        metadata.insert("namespace".to_string(), err.namespace.to_string());
+        metadata.insert("scope".to_string(), (err.scope as i8).to_string());
+        metadata.insert("level".to_string(), err.level.to_string());
+        metadata.insert("retry_mode".to_string(), (err.retry_mode as i8).to_string());
+        metadata.insert(
+            "pass_through_mode".to_string(),
+            (err.pass_through_mode as i8).to_string(),
+        );
+        metadata.insert("mapping_code".to_string(), err.mapping_code.to_string());
+        if let Ok(payload) = serde_json::to_string(&err) {
+            metadata.insert(PAYLOAD_KEY.to_string(), payload);
+        }
+        let details = ErrorDetails::with_error_info(reason, err.namespace.to_string(), metadata);
+        let message = err.message.to_string();
+        Status::with_error_details(Code::from(err.kind as i32), message, details)
+    }
+}
+
+impl From<Status> for WidError {
+    /// Rebuilds the original error when the status came from a `WidError`;
+    /// otherwise maps the gRPC code and message, and reads what it can from an
+    /// `ErrorInfo` detail.
+    #[track_caller]
+    fn from(status: Status) -> Self {
+        let info = status.get_details_error_info();
+        if let Some(err) = info
+            .as_ref()
+            .and_then(|info| info.metadata.get(PAYLOAD_KEY))
+            .and_then(|payload| serde_json::from_str::<WidError>(payload).ok())
+        {
+            return err;
+        }
+        let mut err = WidError::new(ErrorCode::NONE, Message::Default(status.message().into()));
+        err.kind = kind_from_code(status.code());
+        if let Some(info) = info {
+            let get = |key: &str| info.metadata.get(key).and_then(|v| v.parse::<i64>().ok());
+            if let Some(code) = get("code").and_then(|v| ErrorCode::try_new(v as u32).ok()) {
+                err.code = code;
+                err.namespace = code.namespace();
+            }
+            if let Some(namespace) =
+                get("namespace").and_then(|v| Namespace::try_new(v as u32).ok())
+            {
+                err.namespace = namespace;
+            }
+            let variant = |key: &str| get(key).and_then(|v| i8::try_from(v).ok());
+            err.scope = variant("scope")
+                .and_then(|v| Scope::try_from(v).ok())
+                .unwrap_or_default();
+            err.retry_mode = variant("retry_mode")
+                .and_then(|v| RetryMode::try_from(v).ok())
+                .unwrap_or_default();
+            err.pass_through_mode = variant("pass_through_mode")
+                .and_then(|v| PassThroughMode::try_from(v).ok())
+                .unwrap_or_default();
+            err.level = get("level")
+                .and_then(|v| u8::try_from(v).ok())
+                .unwrap_or_default();
+            err.mapping_code = get("mapping_code").unwrap_or_default();
+            err.name = info.reason;
+        }
+        err
+    }
+}
+
+/// `Kind` mirrors `google.rpc.Code`, so the numbers line up.
+fn kind_from_code(code: Code) -> Kind {
+    Kind::try_from(code as i32 as i8).unwrap_or(Kind::Unknown)
+}
@@ -0,0 +1,112 @@
+use crate::{ErrorCode, Kind, Message, WidError};
+
+/// HTTP mapping as documented on each variant, following
+/// https://github.com/googleapis/googleapis/blob/master/google/rpc/code.proto
+impl Kind {
+    pub const fn http_status(self) -> u16 {
+        match self {
+            Kind::Ok => 200,
+            Kind::Cancelled => 499,
+            Kind::Unknown => 500,
+            Kind::InvalidArgument => 400,
+            Kind::DeadlineExceeded => 504,
+            Kind::NotFound => 404,
+            Kind::AlreadyExists => 409,
+            Kind::PermissionDenied => 403,
+            Kind::Unauthenticated => 401,
+            Kind::ResourceExhausted => 429,
+            Kind::FailedPrecondition => 400,
+            Kind::Aborted => 409,
+            Kind::OutOfRange => 400,
+            Kind::Unimplemented => 501,
+            Kind::Internal => 500,
+            Kind::Unavailable => 503,
+            Kind::DataLoss => 500,
+        }
+    }
+
+    /// The reverse mapping used by Google API clients. Statuses shared by
+    /// several kinds map to the most general one (400 to `InvalidArgument`,
+    /// 409 to `Aborted`, 500 to `Internal`).
+    pub const fn from_http_status(status: u16) -> Kind {
+        match status {
+            400 => Kind::InvalidArgument,
+            401 => Kind::Unauthenticated,
+            403 => Kind::PermissionDenied,
+            404 => Kind::NotFound,
+            409 => Kind::Aborted,
+            416 => Kind::OutOfRange,
+            429 => Kind::ResourceExhausted,
+            499 => Kind::Cancelled,
+            500 => Kind::Internal,
+            501 => Kind::Unimplemented,
+            503 => Kind::Unavailable,
+            504 => Kind::DeadlineExceeded,
+            200..=299 => Kind::Ok,
+            402..=498 => Kind::FailedPrecondition,
+            502..=599 => Kind::Internal,
+            _ => Kind::Unknown,
+        }
+    }
+}
+
+impl WidError {
+    /// Rebuilds an error from an HTTP response.
+    ///
+    /// A body holding a serialized `WidError` is returned as is; otherwise the
+    /// kind is derived from `status`, which is also kept in `mapping_code`,
+    /// and the body text (or the reason phrase) becomes the message.
+    #[track_caller]
+    pub fn from_http_response(status: u16, body: &[u8]) -> WidError {
+        if let Ok(err) = serde_json::from_slice::<WidError>(body) {
+            return err;
+        }
+        let text = std::str::from_utf8(body).map(str::trim).unwrap_or_default();
+        let text = if text.is_empty() {
+            reason_phrase(status).map_or_else(|| format!("HTTP {}", status), String::from)
+        } else {
+            text.to_string()
+        };
+        let mut err = WidError::new(ErrorCode::NONE, Message::Default(text.into()));
+        err.kind = Kind::from_http_status(status);
+        err.mapping_code = status as i64;
+        err
+    }
+}
+
+fn reason_phrase(status: u16) -> Option<&'static str> {
+    Some(match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        412 => "Precondition Failed",
+        416 => "Range Not Satisfiable",
+        429 => "Too Many Requests",
+        499 => "Client Closed Request",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "http")]
+impl From<Kind> for ::http::StatusCode {
+    fn from(kind: Kind) -> Self {
+        ::http::StatusCode::from_u16(kind.http_status()).unwrap()
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<::http::StatusCode> for Kind {
+    fn from(status: ::http::StatusCode) -> Self {
+        Kind::from_http_status(status.as_u16())
+    }
+}
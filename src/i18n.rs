@@ -0,0 +1,173 @@
+//! Localization of `Message::I18n` keys.
+//!
+//! A [`MessageCatalog`] maps `(locale, key)` to text. Lookups walk the
+//! locale's fallback chain (`zh-Hant-TW` → `zh-Hant` → `zh`), then the
+//! catalog's default locale, and finally give the catalog's fallback text so
+//! that raw keys never reach users.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::{render, Message, WidError};
+
+pub trait MessageCatalog {
+    /// Text of `key` for exactly `locale`, without fallback.
+    fn lookup(&self, locale: &str, key: &str) -> Option<Cow<'_, str>>;
+
+    /// Locale tried once the requested chain is exhausted.
+    fn default_locale(&self) -> Option<&str> {
+        None
+    }
+
+    /// Text used when no locale has the key.
+    fn fallback_text(&self) -> Cow<'_, str> {
+        Cow::Borrowed("unknown error")
+    }
+
+    /// Text of `key` for `locale` or its closest available fallback.
+    fn resolve(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        fallback_chain(locale)
+            .into_iter()
+            .chain(self.default_locale())
+            .find_map(|locale| self.lookup(locale, key))
+    }
+}
+
+/// `zh-Hant-TW` → `["zh-Hant-TW", "zh-Hant", "zh"]`; `_` is accepted as a
+/// separator too.
+pub fn fallback_chain(locale: &str) -> Vec<&str> {
+    let mut chain = Vec::new();
+    let mut rest = locale.trim();
+    while !rest.is_empty() {
+        chain.push(rest);
+        rest = rest.rfind(['-', '_']).map_or("", |i| &rest[..i]);
+    }
+    chain
+}
+
+/// In-memory catalog of locale bundles.
+///
+/// Locales are matched case-insensitively, and `_` equals `-`.
+#[derive(Debug, Clone, Default)]
+pub struct BundleCatalog {
+    bundles: HashMap<String, HashMap<String, String>>,
+    default_locale: Option<String>,
+    fallback_text: Option<String>,
+}
+
+impl BundleCatalog {
+    pub fn new() -> BundleCatalog {
+        BundleCatalog::default()
+    }
+
+    pub fn with_default_locale(mut self, locale: &str) -> BundleCatalog {
+        self.default_locale = Some(locale.to_string());
+        self
+    }
+
+    pub fn with_fallback_text(mut self, text: &str) -> BundleCatalog {
+        self.fallback_text = Some(text.to_string());
+        self
+    }
+
+    pub fn insert(&mut self, locale: &str, key: &str, text: &str) {
+        self.bundles
+            .entry(normalize(locale))
+            .or_default()
+            .insert(key.to_string(), text.to_string());
+    }
+
+    /// Locales that have a bundle, normalized.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.bundles.keys().map(String::as_str)
+    }
+
+    /// Adds a bundle written in TOML. Nested tables become dotted keys:
+    /// `[order]` `not_found = "..."` defines `order.not_found`.
+    #[cfg(feature = "toml")]
+    pub fn add_toml(&mut self, locale: &str, source: &str) -> Result<(), toml::de::Error> {
+        fn flatten(catalog: &mut BundleCatalog, locale: &str, prefix: &str, table: &toml::Table) {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match value {
+                    toml::Value::Table(table) => flatten(catalog, locale, &key, table),
+                    toml::Value::String(text) => catalog.insert(locale, &key, text),
+                    other => catalog.insert(locale, &key, &other.to_string()),
+                }
+            }
+        }
+        let table: toml::Table = toml::from_str(source)?;
+        flatten(self, locale, "", &table);
+        Ok(())
+    }
+
+    /// Loads every `<locale>.toml` file of `dir`.
+    #[cfg(feature = "toml")]
+    pub fn load_dir<P: AsRef<std::path::Path>>(dir: P) -> std::io::Result<BundleCatalog> {
+        let mut catalog = BundleCatalog::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "toml") {
+                continue;
+            }
+            let Some(locale) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let source = std::fs::read_to_string(&path)?;
+            catalog.add_toml(locale, &source).map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), e),
+                )
+            })?;
+        }
+        Ok(catalog)
+    }
+}
+
+impl MessageCatalog for BundleCatalog {
+    fn lookup(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        self.bundles
+            .get(&normalize(locale))
+            .and_then(|bundle| bundle.get(key))
+            .map(|text| Cow::Borrowed(text.as_str()))
+    }
+
+    fn default_locale(&self) -> Option<&str> {
+        self.default_locale.as_deref()
+    }
+
+    fn fallback_text(&self) -> Cow<'_, str> {
+        match &self.fallback_text {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Borrowed("unknown error"),
+        }
+    }
+}
+
+fn normalize(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+impl WidError {
+    /// The message in `locale`: `Default` text rendered as is, `I18n` keys
+    /// resolved through `catalog` and then rendered, or the catalog's fallback
+    /// text when the key is missing everywhere.
+    pub fn localized_message<C: MessageCatalog + ?Sized>(
+        &self,
+        catalog: &C,
+        locale: &str,
+    ) -> String {
+        match &self.message {
+            Message::Default(template) => template.render(locale),
+            Message::I18n(template) => match catalog.resolve(locale, &template.text) {
+                Some(pattern) => render(&pattern, &template.args, locale),
+                None => catalog.fallback_text().into_owned(),
+            },
+        }
+    }
+}
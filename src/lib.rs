@@ -1,3 +1,46 @@
+#![cfg_attr(widerror_nightly, feature(error_generic_member_access))]
+
+pub use capture::*;
+pub use chain::Chain;
+pub use code::*;
 pub use error::*;
+pub use message::*;
+pub use names::*;
+pub use public::*;
+pub use report::*;
+pub use severity::*;
+pub use source::ForeignError;
+#[cfg(feature = "derive")]
+pub use widerror_derive::WidError;
 
+pub mod api;
+mod capture;
+#[cfg(feature = "toml")]
+pub mod catalog;
+mod chain;
+mod code;
+pub mod details;
 mod error;
+#[cfg(feature = "tonic")]
+pub mod grpc;
+mod http_status;
+pub mod i18n;
+mod message;
+mod names;
+pub mod problem;
+mod public;
+pub mod registry;
+mod report;
+#[cfg(any(feature = "axum", feature = "actix-web"))]
+pub mod response;
+pub mod retry;
+mod severity;
+mod source;
+#[cfg(feature = "tracing")]
+pub mod trace;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "inventory")]
+    pub use inventory;
+}
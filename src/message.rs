@@ -0,0 +1,550 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Error message: literal text, or a key resolved through an
+/// [`i18n::MessageCatalog`](crate::i18n::MessageCatalog).
+///
+/// Both are [`Template`]s and may carry arguments:
+///
+/// ```
+/// use widerror::Message;
+///
+/// let msg = Message::Default("quota of {limit} exceeded for {resource}".into())
+///     .arg("limit", 100)
+///     .arg("resource", "cpu");
+/// assert_eq!(msg.to_string(), "quota of 100 exceeded for cpu");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Message {
+    Default(Template),
+    I18n(Template),
+}
+
+impl Message {
+    /// Adds a named argument.
+    pub fn arg<V: Into<Arg>>(mut self, name: &str, value: V) -> Message {
+        self.template_mut().args.insert(name, value);
+        self
+    }
+
+    pub fn template(&self) -> &Template {
+        match self {
+            Message::Default(template) | Message::I18n(template) => template,
+        }
+    }
+
+    pub fn template_mut(&mut self) -> &mut Template {
+        match self {
+            Message::Default(template) | Message::I18n(template) => template,
+        }
+    }
+
+    pub fn args(&self) -> &Args {
+        &self.template().args
+    }
+}
+
+impl Default for Message {
+    fn default() -> Self {
+        Self::Default(Template::default())
+    }
+}
+
+/// `Default` messages are rendered; `I18n` messages show their key.
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Message::Default(template) => f.write_str(&template.render("")),
+            Message::I18n(template) => f.write_str(&template.text),
+        }
+    }
+}
+
+/// Text (or i18n key) plus its arguments.
+///
+/// Serializes as a plain string when there are no arguments, otherwise as
+/// `{"text": ..., "args": {...}}`, so clients can re-render it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Template {
+    pub text: String,
+    pub args: Args,
+}
+
+impl Template {
+    pub fn new(text: &str) -> Template {
+        Template {
+            text: text.to_string(),
+            args: Args::default(),
+        }
+    }
+
+    /// Renders `text` with `args`, using the plural rules of `locale`.
+    pub fn render(&self, locale: &str) -> String {
+        render(&self.text, &self.args, locale)
+    }
+}
+
+impl From<&str> for Template {
+    fn from(text: &str) -> Self {
+        Template::new(text)
+    }
+}
+
+impl From<String> for Template {
+    fn from(text: String) -> Self {
+        Template {
+            text,
+            args: Args::default(),
+        }
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum TemplateRepr {
+    Text(String),
+    WithArgs {
+        text: String,
+        #[serde(default)]
+        args: Args,
+    },
+}
+
+impl Serialize for Template {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.args.is_empty() {
+            serializer.serialize_str(&self.text)
+        } else {
+            #[derive(Serialize)]
+            struct WithArgs<'a> {
+                text: &'a str,
+                args: &'a Args,
+            }
+            WithArgs {
+                text: &self.text,
+                args: &self.args,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Template {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match TemplateRepr::deserialize(deserializer)? {
+            TemplateRepr::Text(text) => Template::from(text),
+            TemplateRepr::WithArgs { text, args } => Template { text, args },
+        })
+    }
+}
+
+/// Named message arguments, in name order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct Args(BTreeMap<String, Arg>);
+
+impl Args {
+    pub fn insert<V: Into<Arg>>(&mut self, name: &str, value: V) {
+        self.0.insert(name.to_string(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arg> {
+        self.0.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Arg)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A typed argument value, serialized with its type: `{"int": 5}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Arg {
+    Str(String),
+    Int(i64),
+    Decimal(f64),
+    /// milliseconds since the Unix epoch, rendered as RFC 3339 UTC
+    Timestamp(i64),
+}
+
+impl Arg {
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Arg::Int(v) => Some(*v as f64),
+            Arg::Decimal(v) => Some(*v),
+            Arg::Str(v) => v.parse().ok(),
+            Arg::Timestamp(_) => None,
+        }
+    }
+}
+
+impl Display for Arg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Arg::Str(v) => f.write_str(v),
+            Arg::Int(v) => write!(f, "{}", v),
+            Arg::Decimal(v) => write!(f, "{}", v),
+            Arg::Timestamp(millis) => write_rfc3339(f, *millis),
+        }
+    }
+}
+
+impl From<&str> for Arg {
+    fn from(v: &str) -> Self {
+        Arg::Str(v.to_string())
+    }
+}
+
+impl From<String> for Arg {
+    fn from(v: String) -> Self {
+        Arg::Str(v)
+    }
+}
+
+macro_rules! int_arg {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for Arg {
+            fn from(v: $ty) -> Self {
+                Arg::Int(v as i64)
+            }
+        })*
+    };
+}
+
+int_arg!(i8, i16, i32, i64, u8, u16, u32, isize);
+
+macro_rules! wide_int_arg {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for Arg {
+            /// Values beyond `i64::MAX` become an [`Arg::Decimal`] rather than wrapping negative.
+            fn from(v: $ty) -> Self {
+                i64::try_from(v).map_or(Arg::Decimal(v as f64), Arg::Int)
+            }
+        })*
+    };
+}
+
+wide_int_arg!(u64, usize);
+
+impl From<f32> for Arg {
+    fn from(v: f32) -> Self {
+        Arg::Decimal(v as f64)
+    }
+}
+
+impl From<f64> for Arg {
+    fn from(v: f64) -> Self {
+        Arg::Decimal(v)
+    }
+}
+
+impl From<SystemTime> for Arg {
+    fn from(v: SystemTime) -> Self {
+        let millis = match v.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_millis() as i64,
+            Err(e) => -(e.duration().as_millis() as i64),
+        };
+        Arg::Timestamp(millis)
+    }
+}
+
+fn write_rfc3339(f: &mut Formatter<'_>, millis: i64) -> std::fmt::Result {
+    let secs = millis.div_euclid(1000);
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    // civil-from-days, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    write!(
+        f,
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )?;
+    match millis.rem_euclid(1000) {
+        0 => f.write_str("Z"),
+        ms => write!(f, ".{:03}Z", ms),
+    }
+}
+
+/// Renders an ICU-style message pattern:
+///
+/// - `{name}` inserts an argument;
+/// - `{name, plural, =0 {none} one {# item} other {# items}}` picks a branch
+///   by exact value or by the plural category of `locale`, `#` being the
+///   number;
+/// - `{name, select, admin {...} other {...}}` picks a branch by value;
+/// - `'{'` quotes syntax characters, `''` is an apostrophe.
+///
+/// Placeholders with unknown arguments are kept as is.
+pub fn render(pattern: &str, args: &Args, locale: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    render_into(&mut out, pattern, args, locale, None);
+    out
+}
+
+fn render_into(out: &mut String, pattern: &str, args: &Args, locale: &str, number: Option<&Arg>) {
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '\'' => {
+                let after = &rest[1..];
+                if let Some(stripped) = after.strip_prefix('\'') {
+                    out.push('\'');
+                    rest = stripped;
+                } else if after.starts_with(['{', '}', '#']) {
+                    let end = after.find('\'').unwrap_or(after.len());
+                    out.push_str(&after[..end]);
+                    rest = after.get(end + 1..).unwrap_or("");
+                } else {
+                    out.push('\'');
+                    rest = after;
+                }
+            }
+            '#' => {
+                match number {
+                    Some(n) => out.push_str(&n.to_string()),
+                    None => out.push('#'),
+                }
+                rest = &rest[1..];
+            }
+            '{' => match matching_brace(rest) {
+                Some(end) => {
+                    let placeholder = &rest[..=end];
+                    render_placeholder(out, placeholder, args, locale);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    out.push_str(rest);
+                    rest = "";
+                }
+            },
+            _ => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+}
+
+/// Names of the placeholders of `pattern`, those inside plural and select
+/// branches included, in order of first use.
+#[cfg(feature = "toml")]
+pub(crate) fn argument_names(pattern: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    collect_argument_names(pattern, &mut names);
+    names
+}
+
+#[cfg(feature = "toml")]
+fn collect_argument_names<'a>(pattern: &'a str, names: &mut Vec<&'a str>) {
+    let mut rest = pattern;
+    while let Some(i) = rest.find(['\'', '{']) {
+        rest = &rest[i..];
+        if let Some(after) = rest.strip_prefix('\'') {
+            rest = if after.starts_with(['{', '}', '#']) {
+                after.find('\'').map_or("", |end| &after[end + 1..])
+            } else {
+                after.strip_prefix('\'').unwrap_or(after)
+            };
+            continue;
+        }
+        let Some(end) = matching_brace(rest) else {
+            break;
+        };
+        let mut parts = rest[1..end].splitn(3, ',');
+        let name = parts.next().unwrap_or_default().trim();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+        if let (Some("plural" | "select"), Some(branches)) =
+            (parts.next().map(str::trim), parts.next())
+        {
+            for (_, sub) in parse_branches(branches) {
+                collect_argument_names(sub, names);
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+}
+
+/// Byte index of the `}` closing the `{` at the start of `s`.
+fn matching_brace(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' if bytes.get(i + 1) == Some(&b'\'') => i += 1,
+            b'\'' if matches!(bytes.get(i + 1), Some(b'{' | b'}' | b'#')) => {
+                i += 1 + s[i + 1..].find('\'')?;
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn render_placeholder(out: &mut String, placeholder: &str, args: &Args, locale: &str) {
+    let inner = &placeholder[1..placeholder.len() - 1];
+    let mut parts = inner.splitn(3, ',');
+    let name = parts.next().unwrap_or_default().trim();
+    let Some(arg) = args.get(name) else {
+        out.push_str(placeholder);
+        return;
+    };
+    let style = parts.next().map(str::trim);
+    let branches = parts.next().map(parse_branches).unwrap_or_default();
+    let branch = match style {
+        None => {
+            out.push_str(&arg.to_string());
+            return;
+        }
+        Some("plural") => arg.as_number().and_then(|n| {
+            let exact = format!("={}", n);
+            let category = plural_category(locale, n);
+            find_branch(&branches, &exact).or_else(|| find_branch(&branches, category))
+        }),
+        Some("select") => find_branch(&branches, &arg.to_string()),
+        Some(_) => {
+            out.push_str(&arg.to_string());
+            return;
+        }
+    };
+    match branch.or_else(|| find_branch(&branches, "other")) {
+        Some(sub) => {
+            let number = if style == Some("plural") {
+                Some(arg)
+            } else {
+                None
+            };
+            render_into(out, sub, args, locale, number);
+        }
+        None => out.push_str(&arg.to_string()),
+    }
+}
+
+fn find_branch<'a>(branches: &[(&str, &'a str)], selector: &str) -> Option<&'a str> {
+    branches
+        .iter()
+        .find(|(s, _)| *s == selector)
+        .map(|(_, sub)| *sub)
+}
+
+/// `one {# item} other {# items}` → `[("one", "# item"), ("other", "# items")]`
+fn parse_branches(s: &str) -> Vec<(&str, &str)> {
+    let mut branches = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(open) = rest.find('{') {
+        let selector = rest[..open].trim();
+        let Some(end) = matching_brace(&rest[open..]) else {
+            break;
+        };
+        branches.push((selector, &rest[open + 1..open + end]));
+        rest = rest[open + end + 1..].trim_start();
+    }
+    branches
+}
+
+/// CLDR plural category of `n` for the language of `locale`, for the
+/// languages whose rules differ from English.
+pub fn plural_category(locale: &str, n: f64) -> &'static str {
+    let lang = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let integer = n.fract() == 0.0;
+    let i = n.abs() as u64;
+    let (m10, m100) = (i % 10, i % 100);
+    match lang.as_str() {
+        "zh" | "ja" | "ko" | "vi" | "th" | "id" | "ms" | "my" => "other",
+        "fr" | "hi" | "bn" => {
+            if i <= 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" => {
+            if !integer {
+                "other"
+            } else if m10 == 1 && m100 != 11 {
+                "one"
+            } else if (2..=4).contains(&m10) && !(12..=14).contains(&m100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "pl" => {
+            if !integer {
+                "other"
+            } else if i == 1 {
+                "one"
+            } else if (2..=4).contains(&m10) && !(12..=14).contains(&m100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "cs" | "sk" => match (integer, i) {
+            (false, _) => "many",
+            (true, 1) => "one",
+            (true, 2..=4) => "few",
+            _ => "other",
+        },
+        "ar" => match (integer, i, m100) {
+            (false, _, _) => "other",
+            (true, 0, _) => "zero",
+            (true, 1, _) => "one",
+            (true, 2, _) => "two",
+            (true, _, 3..=10) => "few",
+            (true, _, 11..=99) => "many",
+            _ => "other",
+        },
+        _ => {
+            if integer && i == 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::{Kind, PassThroughMode, RetryMode, Scope, Severity};
+
+/// Enums that have a canonical upper snake case name besides their number.
+pub trait CanonicalName:
+    Copy + FromStr<Err = ParseNameError> + TryFrom<i8, Error = ParseNameError> + 'static
+{
+    /// Every variant, in declaration order.
+    const ALL: &'static [Self];
+
+    fn as_str(self) -> &'static str;
+}
+
+/// Returned when a name or number does not match any variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNameError {
+    pub type_name: &'static str,
+    pub value: String,
+}
+
+impl Display for ParseNameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown {} `{}`", self.type_name, self.value)
+    }
+}
+
+impl Error for ParseNameError {}
+
+macro_rules! canonical_names {
+    ($ty:ident { $($variant:ident => $name:literal,)* }) => {
+        canonical_names!($ty as |v: $ty| v as i8, { $($variant => $name,)* });
+    };
+    ($ty:ident as $number:expr, { $($variant:ident => $name:literal,)* }) => {
+        impl $ty {
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $($ty::$variant => $name,)*
+                }
+            }
+        }
+
+        impl CanonicalName for $ty {
+            const ALL: &'static [Self] = &[$($ty::$variant,)*];
+
+            fn as_str(self) -> &'static str {
+                $ty::as_str(self)
+            }
+        }
+
+        /// Accepts the canonical name in any case.
+        impl FromStr for $ty {
+            type Err = ParseNameError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $(if s.eq_ignore_ascii_case($name) {
+                    return Ok($ty::$variant);
+                })*
+                Err(ParseNameError { type_name: stringify!($ty), value: s.to_string() })
+            }
+        }
+
+        impl TryFrom<i8> for $ty {
+            type Error = ParseNameError;
+
+            fn try_from(v: i8) -> Result<Self, ParseNameError> {
+                let number: fn($ty) -> i8 = $number;
+                $(if v == number($ty::$variant) {
+                    return Ok($ty::$variant);
+                })*
+                Err(ParseNameError { type_name: stringify!($ty), value: v.to_string() })
+            }
+        }
+    };
+}
+
+canonical_names!(Kind {
+    Ok => "OK",
+    Cancelled => "CANCELLED",
+    Unknown => "UNKNOWN",
+    InvalidArgument => "INVALID_ARGUMENT",
+    DeadlineExceeded => "DEADLINE_EXCEEDED",
+    NotFound => "NOT_FOUND",
+    AlreadyExists => "ALREADY_EXISTS",
+    PermissionDenied => "PERMISSION_DENIED",
+    ResourceExhausted => "RESOURCE_EXHAUSTED",
+    FailedPrecondition => "FAILED_PRECONDITION",
+    Aborted => "ABORTED",
+    OutOfRange => "OUT_OF_RANGE",
+    Unimplemented => "UNIMPLEMENTED",
+    Internal => "INTERNAL",
+    Unavailable => "UNAVAILABLE",
+    DataLoss => "DATA_LOSS",
+    Unauthenticated => "UNAUTHENTICATED",
+});
+
+canonical_names!(Scope {
+    Internal => "INTERNAL",
+    Clientside => "CLIENTSIDE",
+    Serverside => "SERVERSIDE",
+});
+
+canonical_names!(RetryMode {
+    Unknown => "UNKNOWN",
+    Allowed => "ALLOWED",
+    Denied => "DENIED",
+});
+
+canonical_names!(PassThroughMode {
+    Auto => "AUTO",
+    Should => "SHOULD",
+    Never => "NEVER",
+});
+
+canonical_names!(Severity as |v: Severity| v.syslog_code() as i8, {
+    Debug => "DEBUG",
+    Info => "INFO",
+    Notice => "NOTICE",
+    Warning => "WARNING",
+    Error => "ERROR",
+    Critical => "CRITICAL",
+    Alert => "ALERT",
+    Emergency => "EMERGENCY",
+});
+
+/// Serde helpers that write the canonical name instead of the number, and
+/// read either form (self-describing formats only):
+///
+/// ```
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Log {
+///     #[serde(with = "widerror::serde_names")]
+///     kind: widerror::Kind,
+/// }
+/// ```
+///
+/// `WidError` itself always writes numbers; [`Named`] wraps a single value.
+pub mod serde_names {
+    use std::fmt::Formatter;
+    use std::marker::PhantomData;
+
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    use super::CanonicalName;
+
+    pub fn serialize<T: CanonicalName, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(value.as_str())
+    }
+
+    pub fn deserialize<'de, T: CanonicalName, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        struct NameVisitor<T>(PhantomData<T>);
+
+        impl<T: CanonicalName> Visitor<'_> for NameVisitor<T> {
+            type Value = T;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a canonical name or its number")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> {
+                let v = i8::try_from(v).map_err(E::custom)?;
+                T::try_from(v).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+                let v = i8::try_from(v).map_err(E::custom)?;
+                T::try_from(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(NameVisitor(PhantomData))
+    }
+}
+
+/// A value serialized by its canonical name, read from the name or the
+/// number; see [`serde_names`].
+///
+/// ```
+/// use widerror::{Kind, Named};
+///
+/// let json = serde_json::to_string(&Named(Kind::NotFound)).unwrap();
+/// assert_eq!(json, r#""NOT_FOUND""#);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Named<T>(pub T);
+
+impl<T> From<T> for Named<T> {
+    fn from(value: T) -> Self {
+        Named(value)
+    }
+}
+
+impl<T: CanonicalName> serde::Serialize for Named<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde_names::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: CanonicalName> serde::Deserialize<'de> for Named<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_names::deserialize(deserializer).map(Named)
+    }
+}
@@ -0,0 +1,169 @@
+//! Problem Details for HTTP APIs (RFC 9457, formerly RFC 7807).
+//!
+//! ```json
+//! {
+//!   "type": "https://errors.example.com/10001/#ORDER_NOT_FOUND",
+//!   "title": "ORDER_NOT_FOUND",
+//!   "status": 404,
+//!   "detail": "order 7 not found",
+//!   "instance": "/orders/7",
+//!   "code": 100010001,
+//!   "kind": "NOT_FOUND",
+//!   "retry_mode": "DENIED",
+//!   "details": [...]
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::details::{Details, Help, Link};
+use crate::{ErrorCode, Kind, Message, Namespace, RetryMode, WidError};
+
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// The `type` of problems that have no documentation page.
+pub const ABOUT_BLANK: &str = "about:blank";
+
+/// How `WidError`s map to problem type URIs.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemConfig {
+    /// Type URIs are `{type_base}/{namespace}/#{name}`; `None` gives
+    /// `about:blank`.
+    pub type_base: Option<String>,
+}
+
+impl ProblemConfig {
+    pub fn new(type_base: &str) -> ProblemConfig {
+        ProblemConfig {
+            type_base: Some(type_base.trim_end_matches('/').to_string()),
+        }
+    }
+
+    /// `{type_base}/{namespace}/#{name}`, the anchor of an error in the
+    /// reference generated by `catalog::docs` and published at `type_base`.
+    pub fn doc_url(&self, namespace: Namespace, name: &str) -> Option<String> {
+        match &self.type_base {
+            Some(base) if !name.is_empty() => Some(format!("{}/{}/#{}", base, namespace, name)),
+            _ => None,
+        }
+    }
+
+    /// The type URI of `err`.
+    pub fn type_uri(&self, err: &WidError) -> String {
+        self.doc_url(err.namespace, &err.name)
+            .unwrap_or_else(|| ABOUT_BLANK.to_string())
+    }
+
+    /// A [`Help`] detail linking to the page of `err`, to attach with
+    /// [`WidError::with_detail`].
+    pub fn help(&self, err: &WidError) -> Option<Help> {
+        let url = self.doc_url(err.namespace, &err.name)?;
+        Some(Help {
+            links: vec![Link {
+                description: format!("Reference of {}", err.name),
+                url,
+            }],
+        })
+    }
+}
+
+/// A Problem Details document; members other than the standard ones are kept
+/// in `extensions`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Problem {
+    #[serde(rename = "type", default = "about_blank")]
+    pub type_uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+fn about_blank() -> String {
+    ABOUT_BLANK.to_string()
+}
+
+impl Problem {
+    pub fn with_instance(mut self, instance: &str) -> Problem {
+        self.instance = Some(instance.to_string());
+        self
+    }
+
+    /// Reads a WidError back. The name is the fragment of the type URI, the
+    /// message is `detail` or else `title`, and the kind comes from the `kind`
+    /// member or else `status`, which is also kept in `mapping_code`.
+    #[track_caller]
+    pub fn to_error(&self) -> WidError {
+        let ext = |key: &str| self.extensions.get(key);
+        let code = ext("code")
+            .and_then(Value::as_u64)
+            .and_then(|v| ErrorCode::try_new(u32::try_from(v).ok()?).ok())
+            .unwrap_or(ErrorCode::NONE);
+        let text = self
+            .detail
+            .as_deref()
+            .or(self.title.as_deref())
+            .unwrap_or_default();
+        let mut err = WidError::new(code, Message::Default(text.into()));
+        if let Some((_, name)) = self.type_uri.split_once('#') {
+            err.name = name.to_string();
+        }
+        let status = self.status.unwrap_or(500);
+        err.kind = ext("kind")
+            .and_then(Value::as_str)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(Kind::from_http_status(status));
+        err.mapping_code = status as i64;
+        if let Some(mode) = ext("retry_mode").and_then(Value::as_str) {
+            err.retry_mode = mode.parse().unwrap_or(RetryMode::Unknown);
+        }
+        if let Some(details) = ext("details") {
+            err.details = Details::deserialize(details).unwrap_or_default();
+        }
+        err
+    }
+}
+
+impl WidError {
+    /// The error as a Problem Details document. `detail` is the message
+    /// (apply [`WidError::to_public`] first for external callers).
+    pub fn to_problem(&self, config: &ProblemConfig) -> Problem {
+        let mut extensions = Map::new();
+        if !self.code.is_none() {
+            extensions.insert("code".to_string(), self.code.get().into());
+        }
+        extensions.insert("kind".to_string(), self.kind.as_str().into());
+        extensions.insert("retry_mode".to_string(), self.retry_mode.as_str().into());
+        if !self.details.is_empty() {
+            let details = serde_json::to_value(&self.details).unwrap_or_default();
+            extensions.insert("details".to_string(), details);
+        }
+        let title = if self.name.is_empty() {
+            self.kind.as_str().to_string()
+        } else {
+            self.name.clone()
+        };
+        Problem {
+            type_uri: config.type_uri(self),
+            title: Some(title),
+            status: Some(self.kind.http_status()),
+            detail: Some(self.message.to_string()),
+            instance: None,
+            extensions,
+        }
+    }
+}
+
+impl From<Problem> for WidError {
+    #[track_caller]
+    fn from(problem: Problem) -> Self {
+        problem.to_error()
+    }
+}
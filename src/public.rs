@@ -0,0 +1,151 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+use crate::details::{DebugInfo, ErrorDetail, ErrorInfo, RequestInfo};
+use crate::source::Source;
+use crate::{ErrorCode, Kind, Message, PassThroughMode, Scope, WidError};
+
+/// Decides what of an error may reach an external caller.
+///
+/// `Never` errors are always hidden and `Should` errors always shown; `Auto`
+/// errors are shown when their scope is in `pass_scopes` and their kind is not
+/// in `hidden_kinds`. Hidden errors are replaced by a generic error carrying a
+/// correlation id in a [`RequestInfo`] detail.
+#[derive(Debug, Clone)]
+pub struct PublicPolicy {
+    /// Scopes of `Auto` errors that are shown.
+    pub pass_scopes: Vec<Scope>,
+    /// Kinds of `Auto` errors that are hidden whatever their scope.
+    pub hidden_kinds: Vec<Kind>,
+    /// Whether shown errors keep the shown part of their source chain.
+    /// Foreign sources are always dropped.
+    pub keep_sources: bool,
+    /// Type URLs of details removed from shown errors.
+    pub stripped_details: Vec<String>,
+    /// `ErrorInfo` metadata keys removed from shown errors.
+    pub sensitive_metadata: Vec<String>,
+    /// Code, name and message of the generic error.
+    pub generic_code: ErrorCode,
+    pub generic_name: String,
+    pub generic_message: String,
+    /// Generates correlation ids for errors that do not carry a
+    /// `RequestInfo` already.
+    pub correlation_id: fn() -> String,
+}
+
+impl Default for PublicPolicy {
+    fn default() -> Self {
+        PublicPolicy {
+            pass_scopes: vec![Scope::Clientside, Scope::Serverside],
+            hidden_kinds: vec![Kind::Unknown, Kind::Internal, Kind::DataLoss],
+            keep_sources: false,
+            stripped_details: vec![DebugInfo::TYPE_URL.to_string()],
+            sensitive_metadata: Vec::new(),
+            generic_code: ErrorCode::NONE,
+            generic_name: "INTERNAL".to_string(),
+            generic_message: "internal error".to_string(),
+            correlation_id: new_correlation_id,
+        }
+    }
+}
+
+impl PublicPolicy {
+    /// Whether `err` itself may be shown, ignoring its sources.
+    pub fn is_public(&self, err: &WidError) -> bool {
+        match err.pass_through_mode {
+            PassThroughMode::Never => false,
+            PassThroughMode::Should => true,
+            PassThroughMode::Auto => {
+                self.pass_scopes.contains(&err.scope) && !self.hidden_kinds.contains(&err.kind)
+            }
+        }
+    }
+
+    fn project(&self, err: &WidError) -> WidError {
+        let mut public = WidError {
+            message: err.message.clone(),
+            code: err.code,
+            name: err.name.clone(),
+            namespace: err.namespace,
+            kind: err.kind,
+            scope: err.scope,
+            level: err.level,
+            retry_mode: err.retry_mode,
+            pass_through_mode: err.pass_through_mode,
+            mapping_code: err.mapping_code,
+            details: err.details.clone(),
+            ..WidError::default()
+        };
+        public
+            .details
+            .retain(|d| !self.stripped_details.contains(&d.type_url));
+        for detail in public.details.iter_mut().filter(|d| d.is::<ErrorInfo>()) {
+            if let Some(Value::Object(metadata)) = detail.fields.get_mut("metadata") {
+                metadata.retain(|key, _| !self.sensitive_metadata.contains(key));
+            }
+        }
+        if self.keep_sources {
+            if let Some(source) = err.source_wid().filter(|e| self.is_public(e)) {
+                public.source_error = Some(Source::Wid(Box::new(self.project(source))));
+            }
+        }
+        public
+    }
+
+    fn generic(&self, err: &WidError) -> WidError {
+        let request_id = err
+            .wid_chain()
+            .find_map(|e| e.detail::<RequestInfo>())
+            .map(|info| info.request_id)
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(self.correlation_id);
+        WidError {
+            message: Message::Default(self.generic_message.as_str().into()),
+            code: self.generic_code,
+            name: self.generic_name.clone(),
+            namespace: self.generic_code.namespace(),
+            kind: Kind::Internal,
+            scope: Scope::Serverside,
+            retry_mode: err.retry_mode,
+            pass_through_mode: PassThroughMode::Never,
+            ..WidError::default()
+        }
+        .with_detail(RequestInfo {
+            request_id,
+            serving_data: String::new(),
+        })
+    }
+}
+
+impl WidError {
+    /// The error as it may be sent to an external caller under `policy`.
+    ///
+    /// The correlation id of a hidden error is read with
+    /// `public.detail::<RequestInfo>()` so that the original can be logged
+    /// under it.
+    pub fn to_public(&self, policy: &PublicPolicy) -> WidError {
+        if policy.is_public(self) {
+            policy.project(self)
+        } else {
+            policy.generic(self)
+        }
+    }
+}
+
+/// 16 hex digits mixed from a process-wide counter, the time and a random
+/// seed.
+pub fn new_correlation_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos()),
+    );
+    format!("{:016x}", hasher.finish())
+}
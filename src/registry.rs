@@ -0,0 +1,377 @@
+//! Global registry of error definitions.
+//!
+//! Each crate declares its errors as [`ErrorDef`]s and registers them either
+//! explicitly at startup with [`register`], or at link time with
+//! [`register_error!`](crate::register_error) (requires the `inventory`
+//! feature). The registry rejects duplicate codes, duplicate names and codes
+//! whose 5-digit prefix does not match their declared namespace.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::{OnceLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ErrorCode, Kind, Message, Namespace, PassThroughMode, RetryMode, Scope, WidError};
+
+/// Static description of one error code.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorDef {
+    /// 9 digits [100000000, 999999999]
+    pub code: ErrorCode,
+    pub name: Cow<'static, str>,
+    /// 5 digits [10000, 99999], the prefix of `code`
+    pub namespace: Namespace,
+    pub kind: Kind,
+    /// default message text
+    pub message: Cow<'static, str>,
+    #[serde(default)]
+    pub scope: Scope,
+    /// error level [0, 255], banded by [`Severity`](crate::Severity)
+    #[serde(default)]
+    pub level: u8,
+    #[serde(default)]
+    pub retry_mode: RetryMode,
+    #[serde(default)]
+    pub pass_through_mode: PassThroughMode,
+    #[serde(default)]
+    pub mapping_code: i64,
+    /// i18n key of the message; `message` is then the source-language text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub i18n: Option<Cow<'static, str>>,
+    /// what the error means and what to do about it, for people
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'static, str>>,
+    /// why the error is no longer returned and what replaces it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Cow<'static, str>>,
+}
+
+impl ErrorDef {
+    pub const fn new(
+        code: ErrorCode,
+        name: &'static str,
+        namespace: Namespace,
+        kind: Kind,
+        message: &'static str,
+    ) -> ErrorDef {
+        ErrorDef {
+            code,
+            name: Cow::Borrowed(name),
+            namespace,
+            kind,
+            message: Cow::Borrowed(message),
+            scope: Scope::Internal,
+            level: 0,
+            retry_mode: RetryMode::Unknown,
+            pass_through_mode: PassThroughMode::Auto,
+            mapping_code: 0,
+            i18n: None,
+            description: None,
+            deprecated: None,
+        }
+    }
+
+    /// Creates a `WidError` carrying this definition. Its message is the i18n
+    /// key when there is one, the default text otherwise.
+    #[track_caller]
+    pub fn to_error(&self) -> WidError {
+        let message = match &self.i18n {
+            Some(key) => Message::I18n(key.as_ref().into()),
+            None => Message::Default(self.message.as_ref().into()),
+        };
+        let mut err = WidError::new(self.code, message);
+        err.name = self.name.to_string();
+        err.kind = self.kind;
+        err.scope = self.scope;
+        err.level = self.level;
+        err.retry_mode = self.retry_mode;
+        err.pass_through_mode = self.pass_through_mode;
+        err.mapping_code = self.mapping_code;
+        err
+    }
+
+    fn check(&self) -> Result<(), RegistryError> {
+        if self.code.is_none() {
+            return Err(RegistryError::InvalidCode { code: self.code });
+        }
+        if self.namespace.is_none() {
+            return Err(RegistryError::InvalidNamespace {
+                code: self.code,
+                namespace: self.namespace,
+            });
+        }
+        if !self.namespace.contains(self.code) {
+            return Err(RegistryError::NamespaceMismatch {
+                code: self.code,
+                namespace: self.namespace,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Reasons a definition is refused by the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    InvalidCode {
+        code: ErrorCode,
+    },
+    InvalidNamespace {
+        code: ErrorCode,
+        namespace: Namespace,
+    },
+    NamespaceMismatch {
+        code: ErrorCode,
+        namespace: Namespace,
+    },
+    DuplicateCode {
+        code: ErrorCode,
+        existing: String,
+        rejected: String,
+    },
+    DuplicateName {
+        name: String,
+        existing: ErrorCode,
+        rejected: ErrorCode,
+    },
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::InvalidCode { .. } => write!(f, "error code is not set"),
+            RegistryError::InvalidNamespace { code, namespace } => {
+                write!(
+                    f,
+                    "namespace {} of error code {} is not set",
+                    namespace, code
+                )
+            }
+            RegistryError::NamespaceMismatch { code, namespace } => {
+                write!(
+                    f,
+                    "error code {} does not start with its namespace {}",
+                    code, namespace
+                )
+            }
+            RegistryError::DuplicateCode {
+                code,
+                existing,
+                rejected,
+            } => {
+                write!(
+                    f,
+                    "error code {} is used by both {} and {}",
+                    code, existing, rejected
+                )
+            }
+            RegistryError::DuplicateName {
+                name,
+                existing,
+                rejected,
+            } => {
+                write!(
+                    f,
+                    "error name {} is used by both {} and {}",
+                    name, existing, rejected
+                )
+            }
+        }
+    }
+}
+
+impl Error for RegistryError {}
+
+/// A validated set of error definitions, indexed by code and by name.
+///
+/// Serializes as a list of [`ErrorDef`], which is the registry export format.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(into = "Vec<ErrorDef>", try_from = "Vec<ErrorDef>")]
+pub struct Registry {
+    defs: BTreeMap<ErrorCode, ErrorDef>,
+    names: HashMap<String, ErrorCode>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Builds a registry, reporting every definition that was refused.
+    pub fn from_defs<I: IntoIterator<Item = ErrorDef>>(
+        defs: I,
+    ) -> Result<Registry, Vec<RegistryError>> {
+        let mut registry = Registry::new();
+        registry.register_all(defs)?;
+        Ok(registry)
+    }
+
+    pub fn register(&mut self, def: ErrorDef) -> Result<(), RegistryError> {
+        def.check()?;
+        if let Some(existing) = self.defs.get(&def.code) {
+            return Err(RegistryError::DuplicateCode {
+                code: def.code,
+                existing: existing.name.to_string(),
+                rejected: def.name.to_string(),
+            });
+        }
+        if let Some(&existing) = self.names.get(def.name.as_ref()) {
+            return Err(RegistryError::DuplicateName {
+                name: def.name.to_string(),
+                existing,
+                rejected: def.code,
+            });
+        }
+        self.names.insert(def.name.to_string(), def.code);
+        self.defs.insert(def.code, def);
+        Ok(())
+    }
+
+    /// Registers every definition, keeping the valid ones and returning all
+    /// refusals.
+    pub fn register_all<I: IntoIterator<Item = ErrorDef>>(
+        &mut self,
+        defs: I,
+    ) -> Result<(), Vec<RegistryError>> {
+        let errors: Vec<RegistryError> = defs
+            .into_iter()
+            .filter_map(|def| self.register(def).err())
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn by_code(&self, code: ErrorCode) -> Option<&ErrorDef> {
+        self.defs.get(&code)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&ErrorDef> {
+        self.names.get(name).and_then(|code| self.defs.get(code))
+    }
+
+    /// Iterates over the definitions in code order.
+    pub fn iter(&self) -> impl Iterator<Item = &ErrorDef> {
+        self.defs.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+}
+
+impl From<Registry> for Vec<ErrorDef> {
+    fn from(registry: Registry) -> Self {
+        registry.defs.into_values().collect()
+    }
+}
+
+impl TryFrom<Vec<ErrorDef>> for Registry {
+    type Error = String;
+
+    fn try_from(defs: Vec<ErrorDef>) -> Result<Self, Self::Error> {
+        Registry::from_defs(defs).map_err(|errors| {
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    }
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(ErrorDef);
+
+/// Registers an [`ErrorDef`] at link time; it is picked up the first time the
+/// global registry is used.
+///
+/// ```ignore
+/// widerror::register_error!(ErrorDef::new(
+///     ErrorCode::new(100010001),
+///     "ORDER_NOT_FOUND",
+///     Namespace::new(10001),
+///     Kind::NotFound,
+///     "order not found",
+/// ));
+/// ```
+#[cfg(feature = "inventory")]
+#[macro_export]
+macro_rules! register_error {
+    ($def:expr) => {
+        $crate::__private::inventory::submit! { $def }
+    };
+}
+
+struct Global {
+    registry: RwLock<Registry>,
+    startup_errors: Vec<RegistryError>,
+}
+
+fn global() -> &'static Global {
+    static GLOBAL: OnceLock<Global> = OnceLock::new();
+    GLOBAL.get_or_init(|| {
+        let mut registry = Registry::new();
+        let startup_errors = register_linked(&mut registry);
+        Global {
+            registry: RwLock::new(registry),
+            startup_errors,
+        }
+    })
+}
+
+#[cfg(feature = "inventory")]
+fn register_linked(registry: &mut Registry) -> Vec<RegistryError> {
+    registry
+        .register_all(inventory::iter::<ErrorDef>.into_iter().cloned())
+        .err()
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "inventory"))]
+fn register_linked(_registry: &mut Registry) -> Vec<RegistryError> {
+    Vec::new()
+}
+
+/// Loads the link-time definitions into the global registry and reports the
+/// ones that were refused. Call it once at startup to fail fast on conflicts.
+pub fn init() -> Result<(), Vec<RegistryError>> {
+    let global = global();
+    if global.startup_errors.is_empty() {
+        Ok(())
+    } else {
+        Err(global.startup_errors.clone())
+    }
+}
+
+/// Adds a definition to the global registry.
+pub fn register(def: ErrorDef) -> Result<(), RegistryError> {
+    global().registry.write().unwrap().register(def)
+}
+
+/// Adds definitions to the global registry, returning all refusals.
+pub fn register_all<I: IntoIterator<Item = ErrorDef>>(defs: I) -> Result<(), Vec<RegistryError>> {
+    global().registry.write().unwrap().register_all(defs)
+}
+
+pub fn by_code(code: ErrorCode) -> Option<ErrorDef> {
+    global().registry.read().unwrap().by_code(code).cloned()
+}
+
+pub fn by_name(name: &str) -> Option<ErrorDef> {
+    global().registry.read().unwrap().by_name(name).cloned()
+}
+
+/// Copies the global registry, e.g. to export it as JSON.
+pub fn snapshot() -> Registry {
+    global().registry.read().unwrap().clone()
+}
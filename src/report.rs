@@ -0,0 +1,162 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::details::Help;
+use crate::WidError;
+
+/// How [`Report`] renders an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportStyle {
+    /// `[NOT_FOUND 100010001] order 7 not found`, the same as `{}`.
+    Compact,
+    /// Every layer on one line, separated by `: `, the same as `{:#}`.
+    Inline,
+    /// The error, its numbered causes, location and help links on separate
+    /// lines, the same as `{:?}`.
+    #[default]
+    Multiline,
+    /// `Multiline` with ANSI colors for terminals.
+    Color,
+}
+
+/// A `WidError` rendered in a chosen [`ReportStyle`].
+#[derive(Clone, Copy)]
+pub struct Report<'a> {
+    err: &'a WidError,
+    style: ReportStyle,
+}
+
+impl Report<'_> {
+    pub fn style(mut self, style: ReportStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl WidError {
+    /// A multi-line report; see [`Report::style`] for the other formats.
+    pub fn report(&self) -> Report<'_> {
+        Report {
+            err: self,
+            style: ReportStyle::default(),
+        }
+    }
+}
+
+const RED: &str = "\x1b[1;31m";
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// One layer in compact form; foreign errors print their own text.
+fn layer(f: &mut Formatter<'_>, e: &(dyn Error + 'static)) -> std::fmt::Result {
+    match e.downcast_ref::<WidError>() {
+        Some(wid) => compact(f, wid),
+        None => write!(f, "{}", e),
+    }
+}
+
+fn compact(f: &mut Formatter<'_>, err: &WidError) -> std::fmt::Result {
+    if err.code.is_none() {
+        write!(f, "[{}] {}", err.kind, err.message)
+    } else {
+        write!(f, "[{} {}] {}", err.kind, err.code, err.message)
+    }
+}
+
+fn multiline(f: &mut Formatter<'_>, err: &WidError, color: bool) -> std::fmt::Result {
+    let paint = |code: &'static str| if color { code } else { "" };
+    write!(f, "{}", paint(RED))?;
+    compact(f, err)?;
+    write!(f, "{}", paint(RESET))?;
+    let causes: Vec<_> = err.chain().skip(1).collect();
+    if !causes.is_empty() {
+        write!(f, "\n\n{}Caused by:{}", paint(DIM), paint(RESET))?;
+        for (i, cause) in causes.into_iter().enumerate() {
+            write!(f, "\n    {}: ", i)?;
+            layer(f, cause)?;
+        }
+    }
+    if let Some(location) = err.location() {
+        write!(f, "\n\n{}at {}{}", paint(DIM), location, paint(RESET))?;
+    }
+    let links: Vec<_> = err
+        .details
+        .get_all::<Help>()
+        .into_iter()
+        .flat_map(|help| help.links)
+        .collect();
+    if !links.is_empty() {
+        write!(f, "\n\nHelp:")?;
+        for link in links {
+            write!(
+                f,
+                "\n    {}: {}{}{}",
+                link.description,
+                paint(CYAN),
+                link.url,
+                paint(RESET)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.style {
+            ReportStyle::Compact => compact(f, self.err),
+            ReportStyle::Inline => {
+                compact(f, self.err)?;
+                for cause in self.err.chain().skip(1) {
+                    f.write_str(": ")?;
+                    layer(f, cause)?;
+                }
+                Ok(())
+            }
+            ReportStyle::Multiline => multiline(f, self.err, false),
+            ReportStyle::Color => multiline(f, self.err, true),
+        }
+    }
+}
+
+impl Debug for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// `{}` is [`ReportStyle::Compact`] and `{:#}` is [`ReportStyle::Inline`].
+impl Display for WidError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let style = if f.alternate() {
+            ReportStyle::Inline
+        } else {
+            ReportStyle::Compact
+        };
+        Display::fmt(&self.report().style(style), f)
+    }
+}
+
+/// `{:?}` is [`ReportStyle::Multiline`]; `{:#?}` lists the fields.
+impl Debug for WidError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !f.alternate() {
+            return multiline(f, self, false);
+        }
+        f.debug_struct("WidError")
+            .field("message", &self.message)
+            .field("code", &self.code)
+            .field("name", &self.name)
+            .field("namespace", &self.namespace)
+            .field("kind", &self.kind)
+            .field("scope", &self.scope)
+            .field("level", &self.level)
+            .field("retry_mode", &self.retry_mode)
+            .field("pass_through_mode", &self.pass_through_mode)
+            .field("mapping_code", &self.mapping_code)
+            .field("details", &self.details)
+            .field("source_error", &self.source_error)
+            .finish()
+    }
+}
@@ -0,0 +1,184 @@
+//! Returning `WidError` from axum and actix-web handlers.
+//!
+//! The response is built from the process-wide [`Exposure`]: the error is
+//! first projected with [`WidError::to_public`], its status comes from
+//! [`Kind::http_status`], and its body is either the serialized error or a
+//! Problem Details document. Projected errors that are retryable under
+//! [`Exposure::retry`] get `Retry-After` and `Unauthenticated` ones
+//! `WWW-Authenticate`.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::details::RetryInfo;
+use crate::problem::{ProblemConfig, CONTENT_TYPE as PROBLEM_CONTENT_TYPE};
+use crate::retry::RetryPolicy;
+use crate::{Kind, PublicPolicy, WidError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyFormat {
+    /// The serialized `WidError`, as `application/json`.
+    #[default]
+    Json,
+    /// A Problem Details document, as `application/problem+json`.
+    Problem,
+}
+
+/// What leaves the process when a handler returns a `WidError`.
+#[derive(Debug, Clone)]
+pub struct Exposure {
+    pub policy: PublicPolicy,
+    pub format: BodyFormat,
+    pub problem: ProblemConfig,
+    /// Members removed from `Json` bodies, from the error and from every
+    /// `source_error` it keeps.
+    pub hidden_fields: Vec<String>,
+    /// Decides which public errors get `Retry-After`.
+    pub retry: RetryPolicy,
+    /// `Retry-After` of retryable errors without a `RetryInfo` detail.
+    pub default_retry_after: Option<Duration>,
+    /// `WWW-Authenticate` challenge of `Unauthenticated` errors.
+    pub www_authenticate: String,
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Exposure {
+            policy: PublicPolicy::default(),
+            format: BodyFormat::Json,
+            problem: ProblemConfig::default(),
+            hidden_fields: ["scope", "level", "pass_through_mode", "mapping_code"]
+                .map(String::from)
+                .to_vec(),
+            retry: RetryPolicy::default(),
+            default_retry_after: Some(Duration::from_secs(1)),
+            www_authenticate: "Bearer".to_string(),
+        }
+    }
+}
+
+/// Longest `Retry-After`, in seconds; larger delays are sent as one day.
+const MAX_RETRY_AFTER_SECS: u64 = 86_400;
+
+static EXPOSURE: RwLock<Option<Arc<Exposure>>> = RwLock::new(None);
+
+/// Replaces the process-wide exposure.
+pub fn set_exposure(exposure: Exposure) {
+    *EXPOSURE.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(exposure));
+}
+
+/// The process-wide exposure, `Exposure::default()` until set.
+pub fn exposure() -> Arc<Exposure> {
+    EXPOSURE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// A framework-neutral error response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseParts {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Exposure {
+    pub fn response_parts(&self, err: &WidError) -> ResponseParts {
+        let public = err.to_public(&self.policy);
+        let status = public.kind.http_status();
+        let (content_type, body) = match self.format {
+            BodyFormat::Json => {
+                let mut value = serde_json::to_value(&public).unwrap_or_default();
+                self.hide_fields(&mut value);
+                ("application/json", serde_json::to_vec(&value))
+            }
+            BodyFormat::Problem => (
+                PROBLEM_CONTENT_TYPE,
+                serde_json::to_vec(&public.to_problem(&self.problem)),
+            ),
+        };
+        let mut headers = vec![("content-type", content_type.to_string())];
+        // Read from `public`: a hidden error or a stripped `RetryInfo` must
+        // not show through the header.
+        if self.retry.is_retryable(&public) {
+            let delay = public
+                .detail::<RetryInfo>()
+                .map(|info| info.retry_delay)
+                .or(self.default_retry_after);
+            if let Some(delay) = delay {
+                let secs = delay
+                    .as_secs()
+                    .saturating_add(u64::from(delay.subsec_nanos() > 0))
+                    .min(MAX_RETRY_AFTER_SECS);
+                headers.push(("retry-after", secs.to_string()));
+            }
+        }
+        if public.kind == Kind::Unauthenticated {
+            headers.push(("www-authenticate", self.www_authenticate.clone()));
+        }
+        ResponseParts {
+            status,
+            headers,
+            body: body.unwrap_or_default(),
+        }
+    }
+
+    fn hide_fields(&self, value: &mut serde_json::Value) {
+        if let Some(map) = value.as_object_mut() {
+            map.retain(|key, _| !self.hidden_fields.contains(key));
+            if let Some(source) = map.get_mut("source_error") {
+                self.hide_fields(source);
+            }
+        }
+    }
+}
+
+impl WidError {
+    /// The response under the process-wide [`Exposure`].
+    pub fn response_parts(&self) -> ResponseParts {
+        exposure().response_parts(self)
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum_core::response::IntoResponse for WidError {
+    fn into_response(self) -> axum_core::response::Response {
+        let parts = self.response_parts();
+        let mut response =
+            axum_core::response::Response::new(axum_core::body::Body::from(parts.body));
+        *response.status_mut() = http::StatusCode::from_u16(parts.status)
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        for (name, value) in parts.headers {
+            if let Ok(value) = http::HeaderValue::from_str(&value) {
+                response.headers_mut().append(name, value);
+            }
+        }
+        response
+    }
+}
+
+#[cfg(feature = "actix-web")]
+impl actix_web::ResponseError for WidError {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        let kind = if exposure().policy.is_public(self) {
+            self.kind
+        } else {
+            Kind::Internal
+        };
+        actix_web::http::StatusCode::from_u16(kind.http_status())
+            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> actix_web::HttpResponse {
+        let parts = self.response_parts();
+        let status = actix_web::http::StatusCode::from_u16(parts.status)
+            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = actix_web::HttpResponse::build(status);
+        for header in parts.headers {
+            response.append_header(header);
+        }
+        response.body(parts.body)
+    }
+}
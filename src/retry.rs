@@ -0,0 +1,320 @@
+//! Retrying operations that fail with a `WidError`.
+//!
+//! An error is retried when its `retry_mode` is `Allowed`, or when it is
+//! `Unknown` and its kind is one of [`RetryPolicy::retryable_kinds`]. The wait
+//! before the next attempt is the error's [`RetryInfo`] delay when the server
+//! sent one, otherwise an exponential backoff with jitter. Attempts stop at
+//! [`RetryPolicy::max_attempts`] or when the next wait would pass
+//! [`RetryPolicy::deadline`], and the last error is returned.
+//!
+//! ```
+//! use widerror::retry::{retry, RetryPolicy};
+//! use widerror::{Kind, WidError};
+//!
+//! let mut calls = 0;
+//! let policy = RetryPolicy { initial_backoff: std::time::Duration::ZERO, ..RetryPolicy::default() };
+//! let result = retry(&policy, || {
+//!     calls += 1;
+//!     let mut err = WidError::default();
+//!     err.kind = Kind::Unavailable;
+//!     if calls < 3 { Err(err) } else { Ok(calls) }
+//! });
+//! assert_eq!(result.unwrap(), 3);
+//! ```
+
+// The operations return `WidError` as is; boxing it is up to the caller.
+#![allow(clippy::result_large_err)]
+
+use std::collections::hash_map::RandomState;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::details::RetryInfo;
+use crate::{Kind, RetryMode, WidError};
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first one included.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// Fraction of each backoff that is randomized, in `[0, 1]`.
+    pub jitter: f64,
+    /// Time allowed from the first attempt to the start of the last one.
+    pub deadline: Option<Duration>,
+    /// Kinds retried when the error's `retry_mode` is `Unknown`.
+    pub retryable_kinds: Vec<Kind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.2,
+            deadline: None,
+            retryable_kinds: vec![
+                Kind::Unavailable,
+                Kind::Aborted,
+                Kind::DeadlineExceeded,
+                Kind::ResourceExhausted,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn is_retryable(&self, err: &WidError) -> bool {
+        match err.retry_mode {
+            RetryMode::Allowed => true,
+            RetryMode::Denied => false,
+            RetryMode::Unknown => self.retryable_kinds.contains(&err.kind),
+        }
+    }
+
+    /// The wait before attempt `attempt + 1`, after `attempt` attempts failed
+    /// within `elapsed`, or `None` when `err` must not be retried. A server
+    /// [`RetryInfo`] delay replaces the backoff, capped at `max_backoff`.
+    pub fn next_delay(&self, attempt: u32, elapsed: Duration, err: &WidError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.is_retryable(err) {
+            return None;
+        }
+        let delay = match err.detail::<RetryInfo>() {
+            Some(info) => info.retry_delay.min(self.max_backoff),
+            None => self.backoff(attempt),
+        };
+        match (self.deadline, elapsed.checked_add(delay)) {
+            (Some(deadline), Some(end)) if end > deadline => None,
+            (Some(_), None) => None,
+            _ => Some(delay),
+        }
+    }
+
+    /// Fails on a `multiplier` that is negative or not finite, or a `jitter`
+    /// outside `[0, 1]`. [`RetryPolicy::next_delay`] does not panic on them
+    /// but uses a multiplier of 1 and no jitter instead.
+    pub fn validate(&self) -> Result<(), InvalidRetryPolicy> {
+        if !(self.multiplier.is_finite() && self.multiplier >= 0.0) {
+            return Err(InvalidRetryPolicy {
+                field: "multiplier",
+                value: self.multiplier,
+            });
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(InvalidRetryPolicy {
+                field: "jitter",
+                value: self.jitter,
+            });
+        }
+        Ok(())
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let multiplier = if self.multiplier.is_finite() && self.multiplier >= 0.0 {
+            self.multiplier
+        } else {
+            1.0
+        };
+        let exp = multiplier.powi(attempt.saturating_sub(1).min(i32::MAX as u32) as i32);
+        // Clamped in f64: the exponent overflows long before `max_backoff`
+        // stops it, and `Duration::mul_f64` panics on overflow.
+        let secs = self.initial_backoff.as_secs_f64() * exp.min(f64::MAX);
+        let backoff = Duration::try_from_secs_f64(secs)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        let jitter = if (0.0..=1.0).contains(&self.jitter) {
+            self.jitter
+        } else {
+            0.0
+        };
+        backoff.mul_f64(1.0 - jitter * random_unit())
+    }
+}
+
+/// A [`RetryPolicy`] field out of range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidRetryPolicy {
+    pub field: &'static str,
+    pub value: f64,
+}
+
+impl Display for InvalidRetryPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "retry policy {} cannot be {}", self.field, self.value)
+    }
+}
+
+impl Error for InvalidRetryPolicy {}
+
+/// A value in `[0, 1)`.
+fn random_unit() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Time source of the retry loops.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// Real time; `std::thread::sleep` or `tokio::time::sleep`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A clock that advances only when slept on, and records every sleep.
+#[derive(Debug)]
+pub struct MockClock {
+    start: Instant,
+    state: Mutex<(Duration, Vec<Duration>)>,
+}
+
+impl MockClock {
+    pub fn new() -> MockClock {
+        MockClock {
+            start: Instant::now(),
+            state: Mutex::new((Duration::ZERO, Vec::new())),
+        }
+    }
+
+    /// Moves the clock forward without recording a sleep.
+    pub fn advance(&self, duration: Duration) {
+        self.state.lock().unwrap().0 += duration;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().unwrap().0
+    }
+
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.state.lock().unwrap().1.clone()
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.0 += duration;
+        state.1.push(duration);
+    }
+}
+
+/// Runs `op` until it succeeds or its error may not be retried.
+pub fn retry<T, F>(policy: &RetryPolicy, op: F) -> Result<T, WidError>
+where
+    F: FnMut() -> Result<T, WidError>,
+{
+    retry_with_clock(policy, &SystemClock, op)
+}
+
+pub fn retry_with_clock<T, C, F>(policy: &RetryPolicy, clock: &C, mut op: F) -> Result<T, WidError>
+where
+    C: Clock + ?Sized,
+    F: FnMut() -> Result<T, WidError>,
+{
+    let start = clock.now();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let err = match op() {
+            Ok(v) => return Ok(v),
+            Err(err) => err,
+        };
+        match policy.next_delay(attempt, clock.now() - start, &err) {
+            Some(delay) => clock.sleep(delay),
+            None => return Err(err),
+        }
+    }
+}
+
+/// Time source of the async retry loop.
+#[cfg(feature = "tokio")]
+pub trait AsyncClock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration) -> impl std::future::Future<Output = ()> + Send;
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncClock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl std::future::Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncClock for MockClock {
+    fn now(&self) -> Instant {
+        Clock::now(self)
+    }
+
+    fn sleep(&self, duration: Duration) -> impl std::future::Future<Output = ()> + Send {
+        Clock::sleep(self, duration);
+        std::future::ready(())
+    }
+}
+
+/// Async [`retry`], sleeping with `tokio::time::sleep`.
+#[cfg(feature = "tokio")]
+pub async fn retry_async<T, F, Fut>(policy: &RetryPolicy, op: F) -> Result<T, WidError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, WidError>>,
+{
+    retry_async_with_clock(policy, &SystemClock, op).await
+}
+
+#[cfg(feature = "tokio")]
+pub async fn retry_async_with_clock<T, C, F, Fut>(
+    policy: &RetryPolicy,
+    clock: &C,
+    mut op: F,
+) -> Result<T, WidError>
+where
+    C: AsyncClock,
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, WidError>>,
+{
+    let start = clock.now();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let err = match op().await {
+            Ok(v) => return Ok(v),
+            Err(err) => err,
+        };
+        match policy.next_delay(attempt, clock.now() - start, &err) {
+            Some(delay) => clock.sleep(delay).await,
+            None => return Err(err),
+        }
+    }
+}
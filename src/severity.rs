@@ -0,0 +1,149 @@
+use std::fmt::{Display, Formatter};
+
+use crate::WidError;
+
+/// Named bands over `WidError::level`, ascending, named after the syslog
+/// severities.
+///
+/// Each band spans [`Severity::BAND_WIDTH`] levels, so `level` 1–31 is
+/// `Debug`, 128–159 is `Error` and 224–255 is `Emergency`. Levels between
+/// band bases keep their exact value; only the band is interpreted. Level 0
+/// is unset and reads as `Error`, the default.
+///
+/// The number of a severity, for `{:#}`, `TryFrom<i8>` and
+/// [`serde_names`](crate::serde_names), is its syslog code.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone, Default)]
+#[repr(i8)]
+pub enum Severity {
+    Debug = 0,
+    Info = 1,
+    Notice = 2,
+    Warning = 3,
+    #[default]
+    Error = 4,
+    Critical = 5,
+    Alert = 6,
+    Emergency = 7,
+}
+
+impl Severity {
+    pub const BAND_WIDTH: u8 = 32;
+
+    /// The band `level` belongs to, `Error` for the unset level 0.
+    pub const fn from_level(level: u8) -> Severity {
+        match level / Severity::BAND_WIDTH {
+            0 if level == 0 => Severity::Error,
+            0 => Severity::Debug,
+            1 => Severity::Info,
+            2 => Severity::Notice,
+            3 => Severity::Warning,
+            4 => Severity::Error,
+            5 => Severity::Critical,
+            6 => Severity::Alert,
+            _ => Severity::Emergency,
+        }
+    }
+
+    /// The lowest level of the band; 1 for `Debug`, as 0 is unset.
+    pub const fn level(self) -> u8 {
+        match self {
+            Severity::Debug => 1,
+            _ => self as u8 * Severity::BAND_WIDTH,
+        }
+    }
+
+    /// The syslog severity number: 0 for `Emergency` to 7 for `Debug`.
+    pub const fn syslog_code(self) -> u8 {
+        7 - self as u8
+    }
+
+    pub const fn from_syslog_code(code: u8) -> Option<Severity> {
+        match code {
+            0 => Some(Severity::Emergency),
+            1 => Some(Severity::Alert),
+            2 => Some(Severity::Critical),
+            3 => Some(Severity::Error),
+            4 => Some(Severity::Warning),
+            5 => Some(Severity::Notice),
+            6 => Some(Severity::Info),
+            7 => Some(Severity::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// `{}` prints the canonical name, `{:#}` the syslog number.
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            write!(f, "{}", self.syslog_code())
+        } else {
+            f.write_str(self.as_str())
+        }
+    }
+}
+
+impl WidError {
+    /// The band of `level`.
+    pub fn severity(&self) -> Severity {
+        Severity::from_level(self.level)
+    }
+
+    /// Sets `level` to the base of `severity`.
+    pub fn with_severity(mut self, severity: Severity) -> WidError {
+        self.level = severity.level();
+        self
+    }
+
+    pub fn is_at_least(&self, severity: Severity) -> bool {
+        self.severity() >= severity
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<Severity> for log::Level {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Debug => log::Level::Debug,
+            Severity::Info | Severity::Notice => log::Level::Info,
+            Severity::Warning => log::Level::Warn,
+            _ => log::Level::Error,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<log::Level> for Severity {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Trace | log::Level::Debug => Severity::Debug,
+            log::Level::Info => Severity::Info,
+            log::Level::Warn => Severity::Warning,
+            log::Level::Error => Severity::Error,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<Severity> for tracing::Level {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Debug => tracing::Level::DEBUG,
+            Severity::Info | Severity::Notice => tracing::Level::INFO,
+            Severity::Warning => tracing::Level::WARN,
+            _ => tracing::Level::ERROR,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<tracing::Level> for Severity {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::ERROR => Severity::Error,
+            tracing::Level::WARN => Severity::Warning,
+            tracing::Level::INFO => Severity::Info,
+            _ => Severity::Debug,
+        }
+    }
+}
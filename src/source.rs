@@ -0,0 +1,123 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::WidError;
+
+/// A foreign source error as it was captured for serialization.
+///
+/// Deserialized errors carry this in place of the original object.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ForeignError {
+    /// Rust type name of the original error, e.g. `std::io::error::Error`.
+    pub type_name: String,
+    /// Display text of the original error.
+    pub message: String,
+    /// Display texts of the original error's own sources, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chain: Vec<String>,
+}
+
+impl ForeignError {
+    pub fn capture(type_name: &str, err: &(dyn Error + 'static)) -> ForeignError {
+        let mut chain = Vec::new();
+        let mut next = err.source();
+        while let Some(e) = next {
+            chain.push(e.to_string());
+            next = e.source();
+        }
+        ForeignError {
+            type_name: type_name.to_string(),
+            message: err.to_string(),
+            chain,
+        }
+    }
+}
+
+impl Display for ForeignError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ForeignError {}
+
+#[derive(Clone)]
+pub(crate) enum Source {
+    Wid(Box<WidError>),
+    Foreign {
+        type_name: &'static str,
+        error: Arc<dyn Error + Send + Sync>,
+    },
+    Snapshot(ForeignError),
+}
+
+impl Source {
+    pub(crate) fn new<E: Error + Send + Sync + 'static>(e: E) -> Source {
+        let mut slot = Some(e);
+        if let Some(wid) = (&mut slot as &mut dyn Any).downcast_mut::<Option<WidError>>() {
+            return Source::Wid(Box::new(wid.take().unwrap()));
+        }
+        Source::Foreign {
+            type_name: std::any::type_name::<E>(),
+            error: Arc::new(slot.unwrap()),
+        }
+    }
+
+    pub(crate) fn as_error(&self) -> &(dyn Error + 'static) {
+        match self {
+            Source::Wid(e) => &**e,
+            Source::Foreign { error, .. } => &**error,
+            Source::Snapshot(e) => e,
+        }
+    }
+}
+
+impl Debug for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Wid(e) => Debug::fmt(e, f),
+            Source::Foreign { error, .. } => Debug::fmt(error, f),
+            Source::Snapshot(e) => Debug::fmt(e, f),
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self.as_error(), f)
+    }
+}
+
+/// A `WidError` source is written as a nested error, anything else as a
+/// [`ForeignError`].
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Source::Wid(e) => e.serialize(serializer),
+            Source::Foreign { type_name, error } => {
+                ForeignError::capture(type_name, &**error).serialize(serializer)
+            }
+            Source::Snapshot(e) => e.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Source {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Wid(Box<WidError>),
+            Snapshot(ForeignError),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Wid(e) => Source::Wid(e),
+            Repr::Snapshot(e) => Source::Snapshot(e),
+        })
+    }
+}
@@ -0,0 +1,118 @@
+//! Structured `tracing` output for `WidError`.
+//!
+//! Fields are named `error.*`. A span only records fields declared when it
+//! was created, so declare the ones you want as empty:
+//!
+//! ```
+//! let span = tracing::info_span!("call", error.code = tracing::field::Empty, error.kind = tracing::field::Empty);
+//! widerror::WidError::default().record(&span);
+//! ```
+//!
+//! `error.chain` is the sources as a JSON array in a string,
+//! `[{"code", "name", "kind", "message"}, ...]`, outermost first; foreign
+//! sources only have a `message`.
+//!
+//! With a `tracing_error::ErrorLayer` installed, new errors also capture a
+//! [`SpanTrace`].
+
+use serde_json::{json, Value};
+use tracing::{Level, Span};
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+use crate::WidError;
+
+/// Every field written by [`WidError::record`] and [`WidError::emit`].
+pub const FIELDS: &[&str] = &[
+    "error.code",
+    "error.name",
+    "error.namespace",
+    "error.kind",
+    "error.scope",
+    "error.level",
+    "error.severity",
+    "error.retry_mode",
+    "error.pass_through_mode",
+    "error.mapping_code",
+    "error.message",
+    "error.chain",
+];
+
+pub(crate) fn capture_span_trace() -> Option<SpanTrace> {
+    let trace = SpanTrace::capture();
+    (trace.status() == SpanTraceStatus::CAPTURED).then_some(trace)
+}
+
+/// The sources as a JSON array, outermost first.
+fn chain(err: &WidError) -> Value {
+    Value::Array(
+        err.chain()
+            .skip(1)
+            .map(|e| match e.downcast_ref::<WidError>() {
+                Some(wid) => json!({
+                    "code": wid.code,
+                    "name": wid.name,
+                    "kind": wid.kind.as_str(),
+                    "message": wid.message.to_string(),
+                }),
+                None => json!({ "message": e.to_string() }),
+            })
+            .collect(),
+    )
+}
+
+macro_rules! emit_at {
+    ($level:expr, $err:expr) => {
+        tracing::event!(
+            target: "widerror",
+            $level,
+            error.code = $err.code.get(),
+            error.name = $err.name.as_str(),
+            error.namespace = $err.namespace.get(),
+            error.kind = $err.kind.as_str(),
+            error.scope = $err.scope.as_str(),
+            error.level = $err.level,
+            error.severity = $err.severity().as_str(),
+            error.retry_mode = $err.retry_mode.as_str(),
+            error.pass_through_mode = $err.pass_through_mode.as_str(),
+            error.mapping_code = $err.mapping_code,
+            error.message = %$err.message,
+            error.chain = %chain($err),
+            "{}",
+            $err.message
+        )
+    };
+}
+
+impl WidError {
+    /// Records the fields on `span`; see [`FIELDS`].
+    pub fn record(&self, span: &Span) -> &WidError {
+        span.record("error.code", self.code.get());
+        span.record("error.name", self.name.as_str());
+        span.record("error.namespace", self.namespace.get());
+        span.record("error.kind", self.kind.as_str());
+        span.record("error.scope", self.scope.as_str());
+        span.record("error.level", self.level);
+        span.record("error.severity", self.severity().as_str());
+        span.record("error.retry_mode", self.retry_mode.as_str());
+        span.record("error.pass_through_mode", self.pass_through_mode.as_str());
+        span.record("error.mapping_code", self.mapping_code);
+        span.record("error.message", tracing::field::display(&self.message));
+        span.record("error.chain", tracing::field::display(chain(self)));
+        self
+    }
+
+    /// Logs an event with the fields, at the level of [`WidError::severity`].
+    pub fn emit(&self) {
+        match Level::from(self.severity()) {
+            Level::ERROR => emit_at!(Level::ERROR, self),
+            Level::WARN => emit_at!(Level::WARN, self),
+            Level::INFO => emit_at!(Level::INFO, self),
+            _ => emit_at!(Level::DEBUG, self),
+        }
+    }
+
+    /// The span trace captured at creation.
+    pub fn span_trace(&self) -> Option<&SpanTrace> {
+        self.span_trace.as_ref()
+    }
+}
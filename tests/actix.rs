@@ -0,0 +1,44 @@
+#![cfg(feature = "actix-web")]
+
+use actix_web::{test, web, App};
+use serde_json::Value;
+use widerror::problem::ProblemConfig;
+use widerror::response::{set_exposure, BodyFormat, Exposure};
+use widerror::*;
+
+async fn handler() -> Result<&'static str, WidError> {
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("login required".into()),
+    );
+    err.name = "LOGIN_REQUIRED".into();
+    err.kind = Kind::Unauthenticated;
+    err.scope = Scope::Clientside;
+    Err(err)
+}
+
+#[actix_web::test]
+async fn response_error() {
+    set_exposure(Exposure {
+        format: BodyFormat::Problem,
+        problem: ProblemConfig::new("https://errors.example.com"),
+        ..Exposure::default()
+    });
+    let app = test::init_service(App::new().route("/me", web::get().to(handler))).await;
+    let response = test::call_service(&app, test::TestRequest::get().uri("/me").to_request()).await;
+    assert_eq!(response.status(), 401);
+    assert_eq!(
+        response.headers().get("www-authenticate").unwrap(),
+        "Bearer"
+    );
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/problem+json"
+    );
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(
+        body["type"],
+        "https://errors.example.com/10001/#LOGIN_REQUIRED"
+    );
+    assert_eq!(body["detail"], "login required");
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use widerror::api::*;
+use widerror::*;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Order {
+    id: u32,
+}
+
+fn not_found() -> WidError {
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("order 7 not found".into()),
+    );
+    err.name = "ORDER_NOT_FOUND".into();
+    err.kind = Kind::NotFound;
+    err.scope = Scope::Clientside;
+    err
+}
+
+#[test]
+fn default_envelope() {
+    let ok = serde_json::to_value(ApiResponse::<_>::ok(Order { id: 7 })).unwrap();
+    assert_eq!(ok, json!({"code": 0, "msg": "ok", "data": {"id": 7}}));
+
+    let err = serde_json::to_value(ApiResponse::<Order>::err(not_found())).unwrap();
+    assert_eq!(err["code"], 100010001);
+    assert_eq!(err["msg"], "order 7 not found");
+    assert_eq!(err["error"]["name"], "ORDER_NOT_FOUND");
+    assert!(err.get("data").is_none());
+
+    let back: ApiResponse<Order> = serde_json::from_value(ok).unwrap();
+    assert_eq!(back.into_result().unwrap(), Order { id: 7 });
+    let back: ApiResponse<Order> = serde_json::from_value(err).unwrap();
+    let back = back.into_result().unwrap_err();
+    assert_eq!(back.name, "ORDER_NOT_FOUND");
+    assert_eq!(back.kind, Kind::NotFound);
+}
+
+struct Legacy;
+
+impl Envelope for Legacy {
+    const CODE: &'static str = "errno";
+    const MESSAGE: &'static str = "errmsg";
+    const ERROR: Option<&'static str> = None;
+    const SUCCESS_CODE: i64 = 200;
+    const DATA_ON_ERROR: bool = true;
+}
+
+#[test]
+fn custom_envelope() {
+    let body = serde_json::to_value(ApiResponse::<u32, Legacy>::err(not_found())).unwrap();
+    assert_eq!(
+        body,
+        json!({"errno": 100010001, "errmsg": "order 7 not found", "data": null})
+    );
+    let back: ApiResponse<u32, Legacy> = serde_json::from_value(body).unwrap();
+    let err = back.into_result().unwrap_err();
+    assert_eq!(err.code, 100010001);
+    assert_eq!(err.message.to_string(), "order 7 not found");
+
+    let ok: ApiResponse<u32, Legacy> =
+        serde_json::from_value(json!({"errno": 200, "errmsg": "ok", "data": 3})).unwrap();
+    assert_eq!(ok.into_result().unwrap(), 3);
+}
+
+#[test]
+fn errors_without_code_never_look_successful() {
+    let mut err = WidError::default();
+    err.kind = Kind::Unavailable;
+    err.scope = Scope::Serverside;
+    let body = serde_json::to_value(ApiResponse::<()>::err(err)).unwrap();
+    assert_eq!(body["code"], 503);
+}
+
+struct Internal;
+
+impl Envelope for Internal {
+    fn public_policy() -> Option<PublicPolicy> {
+        None
+    }
+}
+
+#[test]
+fn errors_are_public_by_default() {
+    let mut err = not_found().with_source(std::io::Error::other("disk on fire"));
+    err.scope = Scope::Internal;
+    let body = serde_json::to_value(ApiResponse::<()>::err(err.clone())).unwrap();
+    assert_eq!(body["code"], 500);
+    assert_eq!(body["msg"], "internal error");
+    assert_eq!(body["error"]["name"], "INTERNAL");
+    assert!(!body.to_string().contains("disk on fire"));
+
+    let body = serde_json::to_value(ApiResponse::<(), Internal>::err(err)).unwrap();
+    assert_eq!(body["code"], 100010001);
+    assert_eq!(body["error"]["source_error"]["message"], "disk on fire");
+}
+
+#[test]
+fn unknown_error_shapes() {
+    for (body, code, mapping_code, text) in [
+        (
+            json!({"code": 40001, "msg": "bad token"}),
+            0,
+            40001,
+            "bad token",
+        ),
+        (json!({"code": -1, "error": "boom"}), 0, -1, "boom"),
+        (json!({"error": {"reason": "x"}}), 0, 0, r#"{"reason":"x"}"#),
+        (json!("Bad Gateway"), 0, 0, "Bad Gateway"),
+        (
+            json!({"code": 200010002, "msg": "quota"}),
+            200010002,
+            0,
+            "quota",
+        ),
+    ] {
+        let response: ApiResponse<Order> = serde_json::from_value(body).unwrap();
+        let err = response.into_result().unwrap_err();
+        assert_eq!(err.code, code);
+        assert_eq!(err.mapping_code, mapping_code);
+        assert_eq!(err.message.to_string(), text);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Reply {
+    #[serde(with = "widerror::api::result", flatten)]
+    result: Result<Order, WidError>,
+}
+
+#[test]
+fn result_field_helpers() {
+    let reply = Reply {
+        result: Ok(Order { id: 1 }),
+    };
+    let body = serde_json::to_value(&reply).unwrap();
+    assert_eq!(body, json!({"code": 0, "msg": "ok", "data": {"id": 1}}));
+    let back: Reply = serde_json::from_value(body).unwrap();
+    assert_eq!(back.result.unwrap(), Order { id: 1 });
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use axum::routing::get;
+use axum::Router;
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tower::ServiceExt;
+use widerror::*;
+
+async fn handler() -> Result<&'static str, WidError> {
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("order 7 not found".into()),
+    );
+    err.kind = Kind::Unavailable;
+    err.scope = Scope::Serverside;
+    Err(err)
+}
+
+#[tokio::test]
+async fn into_response() {
+    let app = Router::new().route("/orders/7", get(handler));
+    let response = app
+        .oneshot(http::Request::get("/orders/7").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.headers()["retry-after"], "1");
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["code"], 100010001);
+    assert!(body.get("scope").is_none());
+}
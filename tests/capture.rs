@@ -0,0 +1,63 @@
+#![cfg_attr(widerror_nightly, feature(error_generic_member_access))]
+
+use std::io;
+
+use widerror::*;
+
+/// Line of the `WidError::new` call below.
+const NEW_LINE: u32 = line!() + 3;
+
+fn new_error() -> WidError {
+    WidError::new(ErrorCode::new(100010001), Message::Default("boom".into()))
+}
+
+#[test]
+fn location_of_new() {
+    let location = new_error().location().unwrap();
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), NEW_LINE);
+
+    let line = line!() + 1;
+    let err = WidError::default().with_source(io::Error::other("disk"));
+    assert_eq!(err.location().unwrap().line(), line);
+
+    // An existing location is kept.
+    let err = new_error().with_source(io::Error::other("disk"));
+    assert_eq!(err.location().unwrap().line(), NEW_LINE);
+}
+
+#[test]
+fn backtrace_switch() {
+    set_backtrace_capture(BacktraceCapture::Always);
+    assert!(new_error().backtrace().is_some());
+    set_backtrace_capture(BacktraceCapture::Never);
+    assert!(new_error().backtrace().is_none());
+    set_backtrace_capture(BacktraceCapture::Env);
+    assert_eq!(backtrace_capture(), BacktraceCapture::Env);
+}
+
+#[test]
+fn debug_section_only_on_request() {
+    let err = new_error();
+    let plain = serde_json::to_value(&err).unwrap();
+    assert!(plain.get("location").is_none());
+    assert!(plain.get("debug").is_none());
+
+    let debug = serde_json::to_value(err.debug_view()).unwrap();
+    assert_eq!(debug["code"], 100010001);
+    assert_eq!(debug["debug"]["location"]["file"], file!());
+    assert_eq!(debug["debug"]["location"]["line"], NEW_LINE);
+}
+
+#[cfg(widerror_nightly)]
+#[test]
+fn provided_through_error() {
+    let wid = new_error();
+    let err: &dyn std::error::Error = &wid;
+    assert_eq!(
+        std::error::request_ref::<std::backtrace::Backtrace>(err).is_some(),
+        wid.backtrace().is_some()
+    );
+    let location = std::error::request_ref::<std::panic::Location<'static>>(err).unwrap();
+    assert_eq!(location.line(), NEW_LINE);
+}
@@ -0,0 +1,425 @@
+#![cfg(feature = "toml")]
+
+use widerror::catalog::*;
+use widerror::*;
+
+mod generated {
+    include!("fixtures/catalog/errors.rs");
+}
+
+const FIXTURE: &str = "tests/fixtures/catalog/errors.toml";
+
+#[test]
+fn loads_catalog() {
+    let catalog = Catalog::load(FIXTURE).unwrap();
+    assert_eq!(catalog.namespaces.len(), 2);
+    assert_eq!(catalog.namespaces[0].name, "order");
+    let def = catalog.errors().next().unwrap();
+    assert_eq!(def, &generated::order::ORDER_NOT_FOUND);
+    assert_eq!(def.level, Severity::Error.level());
+    assert_eq!(catalog.to_registry().unwrap().len(), 3);
+}
+
+#[test]
+fn generated_code_is_up_to_date() {
+    let catalog = Catalog::load(FIXTURE).unwrap();
+    let expected = std::fs::read_to_string("tests/fixtures/catalog/errors.rs").unwrap();
+    assert_eq!(catalog.to_rust(), expected);
+}
+
+#[test]
+fn generated_constructors() {
+    let err = generated::order::order_locked(7, "bob");
+    assert_eq!(err.code, 100010002);
+    assert_eq!(err.kind, Kind::Aborted);
+    assert_eq!(err.retry_mode, RetryMode::Allowed);
+    assert_eq!(err.message.to_string(), "order 7 is locked by bob");
+
+    let err = generated::order::order_not_found(7);
+    assert_eq!(err.scope, Scope::Clientside);
+    assert_eq!(err.mapping_code, 404);
+    assert_eq!(
+        err.message,
+        Message::I18n("order.not_found".into()).arg("id", 7)
+    );
+    assert_eq!(generated::ERRORS.len(), 3);
+
+    #[allow(deprecated)]
+    let err = generated::r#type::type_unavailable(3, "bob", "timeout");
+    assert_eq!(err.namespace, generated::r#type::NAMESPACE);
+    assert_eq!(err.name, "TYPE_UNAVAILABLE");
+    // `who` only appears inside plural branches; `err` is not the local.
+    assert_eq!(
+        err.message.to_string(),
+        "type service unavailable for bob and 3 others: timeout"
+    );
+}
+
+#[test]
+fn line_level_diagnostics() {
+    let source = r#"[[namespace]]
+code = 10001
+name = "order"
+
+[[namespace.error]]
+code = 100010001
+name = "ORDER_NOT_FOUND"
+kind = "NOT_FOUND"
+message = "not found"
+
+[[namespace.error]]
+code = 100010001
+name = "order_gone"
+kind = "GONE"
+message = "gone"
+
+[[namespace.error]]
+code = 100020001
+name = "ORDER_NOT_FOUND"
+kind = "ABORTED"
+level = 300
+message = "aborted"
+
+[[namespace.error]]
+code = 100010003
+name = "ORDER_LOCKED"
+kind = "ABORTED"
+message = "order {orderId} is locked by {owner}"
+"#;
+    let err = Catalog::parse(source).unwrap_err();
+    let lines: Vec<(usize, &str)> = err
+        .diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            (12, "error code 100010001 is already used by ORDER_NOT_FOUND at line 6"),
+            (13, "error name `order_gone` is not upper snake case"),
+            (14, "unknown Kind `GONE`, expected one of OK, CANCELLED, UNKNOWN, INVALID_ARGUMENT, DEADLINE_EXCEEDED, NOT_FOUND, ALREADY_EXISTS, PERMISSION_DENIED, RESOURCE_EXHAUSTED, FAILED_PRECONDITION, ABORTED, OUT_OF_RANGE, UNIMPLEMENTED, INTERNAL, UNAVAILABLE, DATA_LOSS, UNAUTHENTICATED"),
+            (18, "error code 100020001 does not start with its namespace 10001"),
+            (19, "error name `ORDER_NOT_FOUND` is already used at line 7"),
+            (21, "level must be a number in [0, 255] or a severity name"),
+            (28, "message argument `orderId` is not lower snake case"),
+        ]
+    );
+    assert_eq!(err.diagnostics[0].column, 8);
+}
+
+#[test]
+fn syntax_errors_have_a_position() {
+    let err = Catalog::parse("[[namespace]]\ncode = 10001\nname = \"order\"\nowner = \"me\"\n")
+        .unwrap_err();
+    assert_eq!(err.diagnostics.len(), 1);
+    assert_eq!(err.diagnostics[0].line, 4);
+
+    let err = Catalog::load("tests/fixtures/catalog/missing.toml").unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("tests/fixtures/catalog/missing.toml: "));
+}
+
+const V1: &str = r#"[[namespace]]
+code = 10001
+name = "order"
+retired = [100010009]
+
+[[namespace.error]]
+code = 100010001
+name = "ORDER_NOT_FOUND"
+kind = "NOT_FOUND"
+message = "order not found"
+
+[[namespace.error]]
+code = 100010002
+name = "ORDER_LOCKED"
+kind = "ABORTED"
+message = "order locked"
+"#;
+
+#[test]
+fn retired_codes_cannot_be_reused() {
+    let catalog = Catalog::parse(V1).unwrap();
+    assert_eq!(catalog.namespaces[0].retired, [ErrorCode::new(100010009)]);
+
+    let reused = V1.replace("code = 100010002", "code = 100010009");
+    let err = Catalog::parse(&reused).unwrap_err();
+    assert_eq!(err.diagnostics.len(), 1);
+    assert_eq!(err.diagnostics[0].line, 13);
+    assert_eq!(
+        err.diagnostics[0].message,
+        "error code 100010009 was retired at line 4"
+    );
+}
+
+#[test]
+fn diff_classifies_changes() {
+    use widerror::catalog::diff::*;
+
+    let v2 = V1
+        .replace("retired = [100010009]", "retired = [100010001]")
+        .replace("code = 100010001", "code = 100010009")
+        .replace(
+            "\"order locked\"",
+            "\"order {id} is locked\"\nretry_mode = \"ALLOWED\"",
+        );
+    let v2 = Catalog::parse(&v2).unwrap();
+    let changes = diff(&Catalog::parse(V1).unwrap(), &v2);
+    assert!(changes.is_breaking());
+    assert_eq!(
+        changes.to_string(),
+        "breaking    100010001 ORDER_NOT_FOUND: removed\n\
+         breaking    100010002 ORDER_LOCKED: retry_mode changed from \"UNKNOWN\" to \"ALLOWED\"\n\
+         compatible  100010002 ORDER_LOCKED: message changed from \"order locked\" to \"order {id} is locked\"\n\
+         breaking    100010009 ORDER_NOT_FOUND: reuses a retired code\n\
+         \n3 breaking and 1 compatible change(s)"
+    );
+    let json = serde_json::to_value(&changes).unwrap();
+    assert_eq!(json["breaking"], true);
+    assert_eq!(
+        json["changes"][1],
+        serde_json::json!({
+            "compatibility": "breaking",
+            "change": "changed",
+            "code": 100010002,
+            "name": "ORDER_LOCKED",
+            "field": "retry_mode",
+            "old": "UNKNOWN",
+            "new": "ALLOWED"
+        })
+    );
+
+    let same = diff(&v2, &v2);
+    assert!(same.is_empty());
+    assert_eq!(same.to_string(), "no changes");
+}
+
+#[test]
+fn diff_reports_retired_codes_and_namespaces() {
+    use widerror::catalog::diff::*;
+
+    let v2 = V1
+        .replace("retired = [100010009]\n", "")
+        .replace("name = \"order\"", "name = \"orders\"")
+        + "\n[[namespace]]\ncode = 10002\nname = \"stock\"\n";
+    let v1 = Catalog::parse(V1).unwrap();
+    let v2 = Catalog::parse(&v2).unwrap();
+    let changes = diff(&v1, &v2);
+    assert_eq!(
+        changes.to_string(),
+        "breaking    namespace 10001 orders: renamed from \"order\"\n\
+         compatible  namespace 10002 stock: added\n\
+         breaking    100010009: no longer retired\n\
+         \n2 breaking and 1 compatible change(s)"
+    );
+    assert_eq!(changes.changes[2].code(), Some(ErrorCode::new(100010009)));
+    let json = serde_json::to_value(&changes).unwrap();
+    assert_eq!(
+        json["changes"][0],
+        serde_json::json!({
+            "compatibility": "breaking",
+            "change": "namespace_renamed",
+            "namespace": 10001,
+            "old": "order",
+            "new": "orders"
+        })
+    );
+
+    let removed = diff(&v2, &v1);
+    assert!(removed.changes.contains(&Change::NamespaceRemoved {
+        namespace: Namespace::new(10002),
+        name: "stock".into(),
+    }));
+    assert_eq!(removed.breaking().count(), 2);
+}
+
+#[test]
+fn other_languages_match_fixtures() {
+    use widerror::catalog::codegen::Generator;
+
+    let catalog = Catalog::load(FIXTURE).unwrap();
+    for (extension, generator) in [
+        ("ts", Generator::typescript()),
+        ("go", Generator::go()),
+        ("kt", Generator::kotlin().with_package("com.example.errors")),
+        ("java", Generator::java().with_package("com.example.errors")),
+        ("json", Generator::json()),
+    ] {
+        let path = format!("tests/fixtures/catalog/errors.{}", extension);
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(generator.render(&catalog).unwrap(), expected, "{}", path);
+    }
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&Generator::json().render(&catalog).unwrap()).unwrap();
+    assert_eq!(manifest["namespaces"][0]["errors"][1]["status"], 409);
+    assert_eq!(
+        manifest["namespaces"][1]["description"],
+        serde_json::Value::Null
+    );
+}
+
+#[test]
+fn empty_namespaces_and_comment_ends() {
+    use widerror::catalog::codegen::Generator;
+
+    let catalog = Catalog::parse(
+        "[[namespace]]\ncode = 10003\nname = \"stock\"\n\
+         description = \"\"\"Stock levels; see /docs/*/stock.\nNothing yet.\"\"\"\n",
+    )
+    .unwrap();
+    for generator in [
+        Generator::typescript(),
+        Generator::go(),
+        Generator::kotlin(),
+        Generator::java(),
+    ] {
+        let code = generator.render(&catalog).unwrap();
+        assert!(!code.contains("/docs/*/"), "{}", code);
+        assert!(code.contains("Stock levels; see /docs/*\\/stock. Nothing yet."));
+    }
+    let java = Generator::java().render(&catalog).unwrap();
+    assert!(java.contains("    enum Stock implements ErrorDef {\n        ;\n"));
+    let typescript = Generator::typescript().render(&catalog).unwrap();
+    assert!(typescript.ends_with("export type ErrorCode =\n  never;\n"));
+    let manifest: serde_json::Value =
+        serde_json::from_str(&Generator::json().render(&catalog).unwrap()).unwrap();
+    assert_eq!(
+        manifest["namespaces"][0]["description"],
+        "Stock levels; see /docs/*/stock.\nNothing yet."
+    );
+}
+
+#[test]
+fn go_constants_do_not_collide() {
+    use widerror::catalog::codegen::Generator;
+
+    let catalog = Catalog::parse(
+        "[[namespace]]\ncode = 10001\nname = \"order\"\n\n\
+         [[namespace.error]]\ncode = 100010001\nname = \"ERRORS\"\nkind = \"ABORTED\"\nmessage = \"a\"\n\n\
+         [[namespace.error]]\ncode = 100010002\nname = \"ERROR_DEF\"\nkind = \"ABORTED\"\nmessage = \"b\"\n",
+    )
+    .unwrap();
+    let go = Generator::go().render(&catalog).unwrap();
+    assert!(go.contains("const ErrErrors uint32 = 100010001\n"));
+    assert!(go.contains("const ErrErrorDef uint32 = 100010002\n"));
+    assert!(go.contains("var Errors = []ErrorDef{\n\t{Code: ErrErrors, "));
+}
+
+#[test]
+fn custom_templates() {
+    use widerror::catalog::codegen::Generator;
+
+    let catalog = Catalog::parse(&V1.replace("order locked", "costs $5")).unwrap();
+    let template = "{{#namespaces}}{{name}}:\n{{#errors}}\n  {{camel_name}} = {{message_str}}{{^last}},{{/last}}\n{{/errors}}\n{{/namespaces}}";
+    assert_eq!(
+        Generator::kotlin()
+            .with_template(template)
+            .render(&catalog)
+            .unwrap(),
+        "order:\n  orderNotFound = \"order not found\",\n  orderLocked = \"costs \\$5\"\n"
+    );
+
+    let err = Generator::go()
+        .with_template("{{#errors}}{{nmae}}{{/errors}}")
+        .render(&catalog)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "template error at byte 11: unknown variable `nmae`"
+    );
+    let err = Generator::go()
+        .with_template("{{#errors}}{{/namespaces}}")
+        .render(&catalog)
+        .unwrap_err();
+    assert_eq!(err.message, "`namespaces` closes section `errors`");
+}
+
+#[test]
+fn reference_pages() {
+    use widerror::catalog::docs::*;
+    use widerror::i18n::BundleCatalog;
+    use widerror::problem::ProblemConfig;
+
+    let catalog = Catalog::load(FIXTURE).unwrap();
+    let messages = BundleCatalog::load_dir("tests/fixtures/i18n").unwrap();
+    let config = ProblemConfig::new("https://errors.example.com/");
+    let site = DocSite {
+        problem: config.clone(),
+        locales: vec!["en".into(), "zh-Hant".into()],
+        ..DocSite::default()
+    };
+    let pages = site.pages(&catalog, Some(&messages));
+    let paths: Vec<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["index.html", "10001/index.html", "10002/index.html"]
+    );
+    assert!(pages[0]
+        .content
+        .contains("<a href=\"10001/index.html\"><code>10001</code></a></td><td>order</td><td>2</td><td>Order service.</td>"));
+
+    // The type URI of an error is the page of its namespace and its anchor.
+    let err = generated::order::order_not_found(7);
+    let type_uri = err.to_problem(&config).type_uri;
+    let (page, anchor) = type_uri
+        .strip_prefix("https://errors.example.com/")
+        .unwrap()
+        .split_once("/#")
+        .unwrap();
+    let order = &pages[1].content;
+    assert_eq!(pages[1].path, format!("{}/index.html", page));
+    assert!(order.contains(&format!("<section id=\"{}\">", anchor)));
+    assert!(order.contains("<h2 id=\"100010001\">"));
+    assert!(order.contains(&format!("<a href=\"{0}\"><code>{0}</code></a>", type_uri)));
+    assert!(order.contains("<tr><th>HTTP status</th><td>404</td></tr>"));
+    assert!(order
+        .contains("<tr><th>Retry</th><td>Do not retry; the same request fails again.</td></tr>"));
+    assert!(order.contains("<tr><td>en</td><td>Order not found</td></tr>"));
+    assert!(order.contains("<tr><td>zh-Hant</td><td>訂單不存在</td></tr>"));
+    assert!(order.contains("<code>order {id} is locked by {owner}</code>"));
+
+    let site = DocSite {
+        format: DocFormat::Markdown,
+        ..site
+    };
+    let pages = site.pages(&catalog, None);
+    assert_eq!(pages[2].path, "10002/index.md");
+    let content = &pages[2].content;
+    assert!(content.contains("- [`100020001` TYPE_UNAVAILABLE](#TYPE_UNAVAILABLE) (deprecated)\n"));
+    assert!(content.contains(
+        "<a id=\"TYPE_UNAVAILABLE\"></a><a id=\"100020001\"></a>\n\n## TYPE_UNAVAILABLE `100020001`\n\n\
+         > **Deprecated:** The type service is being merged into the order service.\n"
+    ));
+    assert!(content
+        .contains("| Retry | Retry with backoff; this kind of error is usually transient. |\n"));
+    assert!(!content.contains("| Locale |"));
+
+    let dir = std::env::temp_dir().join(format!("widerror-docs-{}", std::process::id()));
+    let written = site.write(&catalog, None, &dir).unwrap();
+    assert_eq!(written.len(), 3);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("10002/index.md")).unwrap(),
+        *content
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn registered_errors_by_namespace() {
+    let catalog = Catalog::from_defs(generated::ERRORS.iter().rev().cloned());
+    let names: Vec<&str> = catalog
+        .namespaces
+        .iter()
+        .map(|ns| ns.name.as_str())
+        .collect();
+    assert_eq!(names, ["namespace_10001", "namespace_10002"]);
+    assert_eq!(
+        catalog.namespaces[0].errors,
+        [
+            generated::order::ORDER_NOT_FOUND,
+            generated::order::ORDER_LOCKED
+        ]
+    );
+}
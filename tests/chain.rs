@@ -0,0 +1,64 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+use widerror::*;
+
+/// A foreign error wrapping a `WidError`, as an upstream client would.
+#[derive(Debug)]
+struct Upstream(WidError);
+
+impl Display for Upstream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("upstream call failed")
+    }
+}
+
+impl Error for Upstream {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn wid(code: u32, kind: Kind) -> WidError {
+    let mut err = WidError::new(ErrorCode::new(code), Message::Default("x".into()));
+    err.kind = kind;
+    err
+}
+
+fn sample() -> WidError {
+    let deep = wid(300010001, Kind::Unavailable).with_source(io::Error::other("connection reset"));
+    wid(100010001, Kind::Internal)
+        .with_source(wid(200010001, Kind::Aborted).with_source(Upstream(deep)))
+}
+
+#[test]
+fn walks_every_layer() {
+    let err = sample();
+    let texts: Vec<_> = err.chain().skip(2).map(|e| e.to_string()).collect();
+    assert_eq!(texts[0], "upstream call failed");
+    assert_eq!(err.chain().count(), 5);
+    assert_eq!(err.root_cause().to_string(), "connection reset");
+    assert!(err.root_cause().is::<io::Error>());
+}
+
+#[test]
+fn queries_reach_below_foreign_layers() {
+    let err = sample();
+    assert_eq!(err.source_wid().unwrap().code, 200010001);
+    assert!(err.any_kind(Kind::Unavailable));
+    assert!(!err.any_kind(Kind::NotFound));
+    assert!(err.contains_code(ErrorCode::new(300010001)));
+    assert!(!err.contains_code(ErrorCode::new(400010001)));
+    let found = err.find(|e| e.kind == Kind::Unavailable).unwrap();
+    assert_eq!(found.code, 300010001);
+    assert_eq!(err.wid_chain().count(), 3);
+}
+
+#[test]
+fn single_layer() {
+    let err = WidError::default();
+    assert_eq!(err.chain().count(), 1);
+    assert!(err.root_cause().is::<WidError>());
+    assert!(err.source_wid().is_none());
+}
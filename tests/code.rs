@@ -0,0 +1,79 @@
+use widerror::{CodeError, ErrorCode, Message, Namespace, WidError};
+
+#[test]
+fn decompose() {
+    const CODE: ErrorCode = ErrorCode::new(100230007);
+    assert_eq!(CODE.namespace(), Namespace::new(10023));
+    assert_eq!(CODE.sequence(), 7);
+    assert_eq!(ErrorCode::from_parts(Namespace::new(10023), 7), CODE);
+    assert!(Namespace::new(10023).contains(CODE));
+    assert!(!Namespace::new(10024).contains(CODE));
+
+    let err = WidError::new(CODE, Message::default());
+    assert_eq!(err.namespace, 10023);
+}
+
+#[test]
+fn runtime_validation() {
+    assert_eq!(ErrorCode::try_new(1234), Err(CodeError::InvalidCode(1234)));
+    assert_eq!(
+        ErrorCode::try_new(1_000_000_000),
+        Err(CodeError::InvalidCode(1_000_000_000))
+    );
+    assert_eq!(
+        Namespace::try_new(100),
+        Err(CodeError::InvalidNamespace(100))
+    );
+    assert_eq!(
+        ErrorCode::try_with_namespace(100230007, Namespace::new(10001)),
+        Err(CodeError::NamespaceMismatch {
+            code: 100230007,
+            namespace: 10001
+        })
+    );
+    assert_eq!(
+        ErrorCode::try_from_parts(Namespace::new(10001), 10000),
+        Err(CodeError::InvalidSequence(10000))
+    );
+    assert!(ErrorCode::try_new(0).unwrap().is_none());
+}
+
+#[test]
+#[should_panic(expected = "error code must start with its namespace")]
+fn panics_on_mismatch() {
+    let _ = ErrorCode::with_namespace(100230007, Namespace::new(10001));
+}
+
+#[test]
+fn plain_number_on_the_wire() {
+    let code = ErrorCode::new(100230007);
+    assert_eq!(serde_json::to_string(&code).unwrap(), "100230007");
+    assert_eq!(
+        serde_json::from_str::<ErrorCode>("100230007").unwrap(),
+        code
+    );
+    assert!(serde_json::from_str::<ErrorCode>("1234").is_err());
+    assert_eq!(
+        serde_json::to_string(&Namespace::new(10023)).unwrap(),
+        "10023"
+    );
+}
+
+#[test]
+fn decoded_namespace_matches_the_code() {
+    let err = WidError::new(ErrorCode::new(100010001), Message::default());
+    let mut json = serde_json::to_value(&err).unwrap();
+    let back: WidError = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(back.namespace, 10001);
+
+    json["namespace"] = 10002.into();
+    let err = serde_json::from_value::<WidError>(json.clone()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "error code 100010001 does not start with its namespace 10002"
+    );
+
+    json["namespace"] = 0.into();
+    let back: WidError = serde_json::from_value(json).unwrap();
+    assert_eq!(back.namespace, 10001);
+}
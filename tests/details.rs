@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use widerror::details::*;
+use widerror::*;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Balance {
+    missing_cents: u64,
+}
+
+impl ErrorDetail for Balance {
+    const TYPE_URL: &'static str = "type.example.com/billing.Balance";
+}
+
+fn sample() -> WidError {
+    WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("invalid order".into()),
+    )
+    .with_detail(BadRequest {
+        field_violations: vec![FieldViolation {
+            field: "order.quantity".into(),
+            description: "must be positive".into(),
+        }],
+    })
+    .with_detail(RetryInfo {
+        retry_delay: Duration::from_millis(1500),
+    })
+    .with_detail(Balance { missing_cents: 250 })
+}
+
+#[test]
+fn typed_getters() {
+    let err = sample();
+    assert_eq!(
+        err.detail::<RetryInfo>().unwrap().retry_delay,
+        Duration::from_millis(1500)
+    );
+    assert_eq!(
+        err.detail::<BadRequest>().unwrap().field_violations[0].field,
+        "order.quantity"
+    );
+    assert_eq!(
+        err.detail::<Balance>(),
+        Some(Balance { missing_cents: 250 })
+    );
+    assert_eq!(err.detail::<QuotaFailure>(), None);
+    assert_eq!(err.details.len(), 3);
+}
+
+#[test]
+fn serde_representation() {
+    let value = serde_json::to_value(sample()).unwrap();
+    assert_eq!(
+        value["details"],
+        json!([
+            {
+                "@type": "type.googleapis.com/google.rpc.BadRequest",
+                "fieldViolations": [{"field": "order.quantity", "description": "must be positive"}]
+            },
+            {"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "1.500s"},
+            {"@type": "type.example.com/billing.Balance", "missing_cents": 250}
+        ])
+    );
+
+    let err: WidError = serde_json::from_value(value).unwrap();
+    assert_eq!(err.details, sample().details);
+}
+
+#[test]
+fn empty_details_are_omitted() {
+    let err = WidError::new(ErrorCode::new(100010001), Message::Default("x".into()));
+    let value = serde_json::to_value(&err).unwrap();
+    assert!(value.get("details").is_none());
+
+    let err: WidError = serde_json::from_value(value).unwrap();
+    assert!(err.details.is_empty());
+}
+
+#[test]
+fn duration_forms() {
+    for (delay, text) in [
+        (Duration::from_secs(3), "3s"),
+        (Duration::from_micros(1_000_500), "1.000500s"),
+        (Duration::new(0, 7), "0.000000007s"),
+    ] {
+        let info = RetryInfo { retry_delay: delay };
+        let value = serde_json::to_value(&info).unwrap();
+        assert_eq!(value["retryDelay"], text);
+        assert_eq!(serde_json::from_value::<RetryInfo>(value).unwrap(), info);
+    }
+    assert!(serde_json::from_value::<RetryInfo>(json!({"retryDelay": "3"})).is_err());
+}
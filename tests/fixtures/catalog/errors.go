@@ -0,0 +1,24 @@
+// Code generated by widerror from the error catalog. DO NOT EDIT.
+
+package errcodes
+
+// ErrorDef describes one error code.
+type ErrorDef struct {
+	Code    uint32
+	Name    string
+	Kind    string
+	Message string
+}
+
+// Order service.
+const ErrOrderNotFound uint32 = 100010001
+const ErrOrderLocked uint32 = 100010002
+
+const ErrTypeUnavailable uint32 = 100020001
+
+// Errors holds every error definition in code order.
+var Errors = []ErrorDef{
+	{Code: ErrOrderNotFound, Name: "ORDER_NOT_FOUND", Kind: "NOT_FOUND", Message: "order {id} not found"},
+	{Code: ErrOrderLocked, Name: "ORDER_LOCKED", Kind: "ABORTED", Message: "order {id} is locked by {owner}"},
+	{Code: ErrTypeUnavailable, Name: "TYPE_UNAVAILABLE", Kind: "UNAVAILABLE", Message: "type service unavailable for {count, plural, one {{who}} other {{who} and # others}}: {err}"},
+}
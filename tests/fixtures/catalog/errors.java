@@ -0,0 +1,69 @@
+// @generated by widerror from the error catalog. Do not edit.
+
+package com.example.errors;
+
+public sealed interface ErrorDef {
+    int code();
+
+    String name();
+
+    String kind();
+
+    String message();
+
+    /** Order service. */
+    enum Order implements ErrorDef {
+        ORDER_NOT_FOUND(100010001, "NOT_FOUND", "order {id} not found"),
+        ORDER_LOCKED(100010002, "ABORTED", "order {id} is locked by {owner}"),
+        ;
+
+        private final int code;
+        private final String kind;
+        private final String message;
+
+        Order(int code, String kind, String message) {
+            this.code = code;
+            this.kind = kind;
+            this.message = message;
+        }
+
+        public int code() {
+            return code;
+        }
+
+        public String kind() {
+            return kind;
+        }
+
+        public String message() {
+            return message;
+        }
+    }
+
+    enum Type implements ErrorDef {
+        TYPE_UNAVAILABLE(100020001, "UNAVAILABLE", "type service unavailable for {count, plural, one {{who}} other {{who} and # others}}: {err}"),
+        ;
+
+        private final int code;
+        private final String kind;
+        private final String message;
+
+        Type(int code, String kind, String message) {
+            this.code = code;
+            this.kind = kind;
+            this.message = message;
+        }
+
+        public int code() {
+            return code;
+        }
+
+        public String kind() {
+            return kind;
+        }
+
+        public String message() {
+            return message;
+        }
+    }
+}
@@ -0,0 +1,29 @@
+// @generated by widerror from the error catalog. Do not edit.
+
+package com.example.errors
+
+sealed class ErrorDef(val code: Int, val name: String, val kind: String, val message: String) {
+    /** Order service. */
+    sealed class Order(code: Int, name: String, kind: String, message: String) :
+        ErrorDef(code, name, kind, message) {
+        object OrderNotFound : Order(100010001, "ORDER_NOT_FOUND", "NOT_FOUND", "order {id} not found")
+        object OrderLocked : Order(100010002, "ORDER_LOCKED", "ABORTED", "order {id} is locked by {owner}")
+    }
+
+    sealed class Type(code: Int, name: String, kind: String, message: String) :
+        ErrorDef(code, name, kind, message) {
+        object TypeUnavailable : Type(100020001, "TYPE_UNAVAILABLE", "UNAVAILABLE", "type service unavailable for {count, plural, one {{who}} other {{who} and # others}}: {err}")
+    }
+
+    companion object {
+        val all: List<ErrorDef> by lazy {
+            listOf(
+                Order.OrderNotFound,
+                Order.OrderLocked,
+                Type.TypeUnavailable,
+            )
+        }
+
+        fun byCode(code: Int): ErrorDef? = all.find { it.code == code }
+    }
+}
@@ -0,0 +1,96 @@
+// @generated by widerror::catalog. Do not edit.
+
+/// Order service.
+pub mod order {
+    pub const NAMESPACE: ::widerror::Namespace = ::widerror::Namespace::new(10001);
+
+    /// The order does not exist or was deleted.
+    pub const ORDER_NOT_FOUND: ::widerror::registry::ErrorDef = ::widerror::registry::ErrorDef {
+        code: ::widerror::ErrorCode::new(100010001),
+        name: ::std::borrow::Cow::Borrowed("ORDER_NOT_FOUND"),
+        namespace: NAMESPACE,
+        kind: ::widerror::Kind::NotFound,
+        message: ::std::borrow::Cow::Borrowed("order {id} not found"),
+        scope: ::widerror::Scope::Clientside,
+        level: 128,
+        retry_mode: ::widerror::RetryMode::Denied,
+        pass_through_mode: ::widerror::PassThroughMode::Should,
+        mapping_code: 404,
+        i18n: Some(::std::borrow::Cow::Borrowed("order.not_found")),
+        description: Some(::std::borrow::Cow::Borrowed("The order does not exist or was deleted.")),
+        deprecated: None,
+    };
+
+    /// Creates [`ORDER_NOT_FOUND`].
+    #[track_caller]
+    pub fn order_not_found(id: impl Into<::widerror::Arg>) -> ::widerror::WidError {
+        let mut __err = ORDER_NOT_FOUND.to_error();
+        __err.message.template_mut().args.insert("id", id);
+        __err
+    }
+
+    /// order {id} is locked by {owner}
+    pub const ORDER_LOCKED: ::widerror::registry::ErrorDef = ::widerror::registry::ErrorDef {
+        code: ::widerror::ErrorCode::new(100010002),
+        name: ::std::borrow::Cow::Borrowed("ORDER_LOCKED"),
+        namespace: NAMESPACE,
+        kind: ::widerror::Kind::Aborted,
+        message: ::std::borrow::Cow::Borrowed("order {id} is locked by {owner}"),
+        scope: ::widerror::Scope::Internal,
+        level: 160,
+        retry_mode: ::widerror::RetryMode::Allowed,
+        pass_through_mode: ::widerror::PassThroughMode::Auto,
+        mapping_code: 0,
+        i18n: None,
+        description: None,
+        deprecated: None,
+    };
+
+    /// Creates [`ORDER_LOCKED`].
+    #[track_caller]
+    pub fn order_locked(id: impl Into<::widerror::Arg>, owner: impl Into<::widerror::Arg>) -> ::widerror::WidError {
+        let mut __err = ORDER_LOCKED.to_error();
+        __err.message.template_mut().args.insert("id", id);
+        __err.message.template_mut().args.insert("owner", owner);
+        __err
+    }
+}
+
+pub mod r#type {
+    pub const NAMESPACE: ::widerror::Namespace = ::widerror::Namespace::new(10002);
+
+    /// type service unavailable for {count, plural, one {{who}} other {{who} and # others}}: {err}
+    pub const TYPE_UNAVAILABLE: ::widerror::registry::ErrorDef = ::widerror::registry::ErrorDef {
+        code: ::widerror::ErrorCode::new(100020001),
+        name: ::std::borrow::Cow::Borrowed("TYPE_UNAVAILABLE"),
+        namespace: NAMESPACE,
+        kind: ::widerror::Kind::Unavailable,
+        message: ::std::borrow::Cow::Borrowed("type service unavailable for {count, plural, one {{who}} other {{who} and # others}}: {err}"),
+        scope: ::widerror::Scope::Serverside,
+        level: 0,
+        retry_mode: ::widerror::RetryMode::Unknown,
+        pass_through_mode: ::widerror::PassThroughMode::Auto,
+        mapping_code: 0,
+        i18n: None,
+        description: None,
+        deprecated: Some(::std::borrow::Cow::Borrowed("The type service is being merged into the order service.")),
+    };
+
+    /// Creates [`TYPE_UNAVAILABLE`].
+    #[deprecated(note = "The type service is being merged into the order service.")]
+    #[track_caller]
+    pub fn type_unavailable(count: impl Into<::widerror::Arg>, who: impl Into<::widerror::Arg>, err: impl Into<::widerror::Arg>) -> ::widerror::WidError {
+        let mut __err = TYPE_UNAVAILABLE.to_error();
+        __err.message.template_mut().args.insert("count", count);
+        __err.message.template_mut().args.insert("who", who);
+        __err.message.template_mut().args.insert("err", err);
+        __err
+    }
+}
+
+/// Every definition of the catalog.
+pub const ERRORS: &[::widerror::registry::ErrorDef] = &[
+    order::ORDER_NOT_FOUND,
+    order::ORDER_LOCKED,
+    r#type::TYPE_UNAVAILABLE,
+];
@@ -0,0 +1,28 @@
+// @generated by widerror from the error catalog. Do not edit.
+
+export interface ErrorDef {
+  readonly code: number;
+  readonly name: string;
+  readonly kind: string;
+  readonly message: string;
+}
+
+/** Order service. */
+export const OrderErrors = {
+  ORDER_NOT_FOUND: { code: 100010001, name: "ORDER_NOT_FOUND", kind: "NOT_FOUND", message: "order {id} not found" },
+  ORDER_LOCKED: { code: 100010002, name: "ORDER_LOCKED", kind: "ABORTED", message: "order {id} is locked by {owner}" },
+} as const satisfies Record<string, ErrorDef>;
+
+export type OrderErrorName = keyof typeof OrderErrors;
+
+export const TypeErrors = {
+  TYPE_UNAVAILABLE: { code: 100020001, name: "TYPE_UNAVAILABLE", kind: "UNAVAILABLE", message: "type service unavailable for {count, plural, one {{who}} other {{who} and # others}}: {err}" },
+} as const satisfies Record<string, ErrorDef>;
+
+export type TypeErrorName = keyof typeof TypeErrors;
+
+/** Every error code of the catalog. */
+export type ErrorCode =
+  | 100010001
+  | 100010002
+  | 100020001;
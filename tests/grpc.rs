@@ -0,0 +1,125 @@
+#![cfg(feature = "tonic")]
+
+use std::convert::Infallible;
+use std::task::{Context, Poll};
+
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::body::Body;
+use tonic::codegen::{http, BoxFuture, Service};
+use tonic::server::NamedService;
+use tonic::transport::{Channel, Server};
+use tonic::{Code, Request, Status};
+use widerror::*;
+
+/// A gRPC service whose every method fails with the configured error.
+#[derive(Clone)]
+struct Failing(WidError);
+
+impl NamedService for Failing {
+    const NAME: &'static str = "widerror.test.Failing";
+}
+
+impl Service<http::Request<Body>> for Failing {
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _req: http::Request<Body>) -> Self::Future {
+        let status = Status::from(self.0.clone());
+        Box::pin(async move { Ok(status.into_http()) })
+    }
+}
+
+async fn call(err: WidError) -> Status {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(Failing(err))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    let channel = Channel::from_shared(format!("http://{}", addr))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut client = tonic::client::Grpc::new(channel);
+    client.ready().await.unwrap();
+    client
+        .unary::<(), (), _>(
+            Request::new(()),
+            http::uri::PathAndQuery::from_static("/widerror.test.Failing/Call"),
+            tonic_prost::ProstCodec::default(),
+        )
+        .await
+        .unwrap_err()
+}
+
+fn sample() -> WidError {
+    let mut cause = WidError::new(
+        ErrorCode::new(100020003),
+        Message::I18n("inventory.empty".into()),
+    );
+    cause.kind = Kind::Unavailable;
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("order 7 not found".into()),
+    )
+    .with_source(cause);
+    err.name = "ORDER_NOT_FOUND".into();
+    err.kind = Kind::NotFound;
+    err.scope = Scope::Clientside;
+    err.level = 3;
+    err.retry_mode = RetryMode::Denied;
+    err.pass_through_mode = PassThroughMode::Should;
+    err.mapping_code = -404;
+    err
+}
+
+#[tokio::test]
+async fn round_trip_over_grpc() {
+    let status = call(sample()).await;
+    assert_eq!(status.code(), Code::NotFound);
+    assert_eq!(status.message(), "order 7 not found");
+
+    let err = WidError::from(status);
+    let expected = sample();
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        serde_json::to_value(&expected).unwrap()
+    );
+}
+
+#[test]
+fn from_foreign_status() {
+    let err = WidError::from(Status::unavailable("try later"));
+    assert_eq!(err.kind, Kind::Unavailable);
+    assert!(err.code.is_none());
+    assert!(matches!(err.message, Message::Default(ref m) if m.text == "try later"));
+}
+
+#[test]
+fn out_of_range_fields_are_not_truncated() {
+    use std::collections::HashMap;
+    use tonic_types::{ErrorDetails, StatusExt};
+
+    for (level, expected) in [("300", 0), ("256", 0), ("-1", 0), ("160", 160)] {
+        let metadata = HashMap::from([
+            ("code".to_string(), "100010001".to_string()),
+            ("level".to_string(), level.to_string()),
+        ]);
+        let status = Status::with_error_details(
+            Code::Aborted,
+            "locked",
+            ErrorDetails::with_error_info("ORDER_LOCKED", "10001", metadata),
+        );
+        let err = WidError::from(status);
+        assert_eq!(err.code, 100010001);
+        assert_eq!(err.level, expected, "level {}", level);
+    }
+}
@@ -0,0 +1,65 @@
+use widerror::{ErrorCode, Kind, Message, WidError};
+
+#[test]
+fn kind_to_status() {
+    assert_eq!(Kind::Ok.http_status(), 200);
+    assert_eq!(Kind::NotFound.http_status(), 404);
+    assert_eq!(Kind::AlreadyExists.http_status(), 409);
+    assert_eq!(Kind::Cancelled.http_status(), 499);
+    assert_eq!(Kind::Unavailable.http_status(), 503);
+}
+
+#[test]
+fn status_to_kind() {
+    for kind in [
+        Kind::Cancelled,
+        Kind::InvalidArgument,
+        Kind::DeadlineExceeded,
+        Kind::NotFound,
+        Kind::PermissionDenied,
+        Kind::Unauthenticated,
+        Kind::ResourceExhausted,
+        Kind::Aborted,
+        Kind::Unimplemented,
+        Kind::Internal,
+        Kind::Unavailable,
+    ] {
+        assert_eq!(Kind::from_http_status(kind.http_status()), kind);
+    }
+    assert_eq!(Kind::from_http_status(204), Kind::Ok);
+    assert_eq!(Kind::from_http_status(412), Kind::FailedPrecondition);
+    assert_eq!(Kind::from_http_status(502), Kind::Internal);
+    assert_eq!(Kind::from_http_status(302), Kind::Unknown);
+}
+
+#[test]
+fn from_http_response() {
+    let mut original = WidError::new(ErrorCode::new(100010001), Message::Default("gone".into()));
+    original.kind = Kind::NotFound;
+    let body = serde_json::to_vec(&original).unwrap();
+    let err = WidError::from_http_response(404, &body);
+    assert_eq!(err.code, 100010001);
+    assert_eq!(err.kind, Kind::NotFound);
+
+    let err = WidError::from_http_response(503, b"upstream overloaded\n");
+    assert_eq!(err.kind, Kind::Unavailable);
+    assert_eq!(err.mapping_code, 503);
+    assert!(matches!(err.message, Message::Default(ref m) if m.text == "upstream overloaded"));
+
+    let err = WidError::from_http_response(429, b"");
+    assert_eq!(err.kind, Kind::ResourceExhausted);
+    assert!(matches!(err.message, Message::Default(ref m) if m.text == "Too Many Requests"));
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn status_code_conversion() {
+    assert_eq!(
+        http::StatusCode::from(Kind::NotFound),
+        http::StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        Kind::from(http::StatusCode::GATEWAY_TIMEOUT),
+        Kind::DeadlineExceeded
+    );
+}
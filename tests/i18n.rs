@@ -0,0 +1,71 @@
+use widerror::i18n::{fallback_chain, BundleCatalog, MessageCatalog};
+use widerror::*;
+
+fn i18n_error(key: &str) -> WidError {
+    WidError::new(ErrorCode::new(100010001), Message::I18n(key.into()))
+}
+
+#[test]
+fn chain() {
+    assert_eq!(
+        fallback_chain("zh-Hant-TW"),
+        vec!["zh-Hant-TW", "zh-Hant", "zh"]
+    );
+    assert_eq!(fallback_chain("en_US"), vec!["en_US", "en"]);
+    assert!(fallback_chain("").is_empty());
+}
+
+#[test]
+fn resolves_through_fallbacks() {
+    let mut catalog = BundleCatalog::new()
+        .with_default_locale("en")
+        .with_fallback_text("Something went wrong");
+    catalog.insert("en", "order.not_found", "Order not found");
+    catalog.insert("zh", "order.not_found", "订单不存在");
+    catalog.insert("zh_Hant", "order.not_found", "訂單不存在");
+
+    let err = i18n_error("order.not_found");
+    assert_eq!(err.localized_message(&catalog, "zh-Hant-TW"), "訂單不存在");
+    assert_eq!(err.localized_message(&catalog, "zh-CN"), "订单不存在");
+    assert_eq!(err.localized_message(&catalog, "fr-FR"), "Order not found");
+    assert_eq!(
+        catalog.resolve("fr", "order.not_found").unwrap(),
+        "Order not found"
+    );
+
+    let missing = i18n_error("order.missing");
+    assert_eq!(
+        missing.localized_message(&catalog, "zh"),
+        "Something went wrong"
+    );
+
+    let plain = WidError::new(ErrorCode::NONE, Message::Default("plain text".into()));
+    assert_eq!(plain.localized_message(&catalog, "zh"), "plain text");
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn loads_toml_bundles() {
+    let catalog =
+        BundleCatalog::load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/i18n"))
+            .unwrap()
+            .with_default_locale("en");
+    assert_eq!(catalog.locales().count(), 3);
+
+    assert_eq!(
+        i18n_error("order.not_found").localized_message(&catalog, "zh-Hant-HK"),
+        "訂單不存在"
+    );
+    assert_eq!(
+        i18n_error("order.locked").localized_message(&catalog, "zh-Hant-HK"),
+        "订单已锁定"
+    );
+    assert_eq!(
+        i18n_error("order.locked").localized_message(&catalog, "de"),
+        "Order is locked"
+    );
+    assert_eq!(
+        i18n_error("order.nope").localized_message(&catalog, "de"),
+        "unknown error"
+    );
+}
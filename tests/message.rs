@@ -0,0 +1,100 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use widerror::i18n::BundleCatalog;
+use widerror::*;
+
+#[test]
+fn interpolation() {
+    let msg = Message::Default("quota of {limit} exceeded for {resource}".into())
+        .arg("limit", 100)
+        .arg("resource", "cpu");
+    assert_eq!(msg.to_string(), "quota of 100 exceeded for cpu");
+
+    let args = Message::default()
+        .arg("ratio", 0.5)
+        .arg("at", UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))
+        .args()
+        .clone();
+    assert_eq!(
+        render("{ratio} at {at}", &args, ""),
+        "0.5 at 2023-11-14T22:13:20.123Z"
+    );
+    assert_eq!(
+        render("{missing} '{literal}' it''s", &args, ""),
+        "{missing} {literal} it's"
+    );
+}
+
+#[test]
+fn plural_and_select() {
+    let pattern = "{count, plural, =0 {no files} one {# file} other {# files}} by {role, select, admin {an admin} other {a user}}";
+    let render_with = |count: i64, role: &str, locale: &str| {
+        Message::Default(pattern.into())
+            .arg("count", count)
+            .arg("role", role)
+            .template()
+            .render(locale)
+    };
+    assert_eq!(render_with(0, "admin", "en"), "no files by an admin");
+    assert_eq!(render_with(1, "guest", "en"), "1 file by a user");
+    assert_eq!(render_with(5, "guest", "en"), "5 files by a user");
+
+    let ru = "{n, plural, one {# файл} few {# файла} many {# файлов} other {# файла}}";
+    let ru_with = |n: i64| render(ru, &Message::default().arg("n", n).args().clone(), "ru-RU");
+    assert_eq!(ru_with(21), "21 файл");
+    assert_eq!(ru_with(3), "3 файла");
+    assert_eq!(ru_with(11), "11 файлов");
+    assert_eq!(plural_category("zh-Hant", 1.0), "other");
+    assert_eq!(plural_category("fr", 0.0), "one");
+}
+
+#[test]
+fn wide_unsigned_args_do_not_wrap() {
+    assert_eq!(Arg::from(u64::MAX), Arg::Decimal(u64::MAX as f64));
+    assert_eq!(Arg::from(i64::MAX as u64), Arg::Int(i64::MAX));
+    assert_eq!(Arg::from(7usize), Arg::Int(7));
+
+    let msg =
+        Message::Default("{n, plural, one {# file} other {many files}}".into()).arg("n", u64::MAX);
+    assert_eq!(msg.to_string(), "many files");
+    assert!(!msg.args().get("n").unwrap().to_string().starts_with('-'));
+}
+
+#[test]
+fn serialized_with_args() {
+    let plain = Message::Default("plain".into());
+    assert_eq!(
+        serde_json::to_string(&plain).unwrap(),
+        r#"{"Default":"plain"}"#
+    );
+
+    let msg = Message::I18n("quota.exceeded".into())
+        .arg("limit", 100)
+        .arg("resource", "cpu");
+    let json = serde_json::to_string(&msg).unwrap();
+    assert_eq!(
+        json,
+        r#"{"I18n":{"text":"quota.exceeded","args":{"limit":{"int":100},"resource":{"str":"cpu"}}}}"#
+    );
+    assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), msg);
+}
+
+#[test]
+fn localized_with_args() {
+    let mut catalog = BundleCatalog::new();
+    catalog.insert(
+        "en",
+        "cart.items",
+        "{count, plural, one {# item} other {# items}} in cart",
+    );
+    catalog.insert("zh", "cart.items", "购物车中有 {count} 件商品");
+    let err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::I18n("cart.items".into()).arg("count", 1),
+    );
+    assert_eq!(err.localized_message(&catalog, "en-US"), "1 item in cart");
+    assert_eq!(
+        err.localized_message(&catalog, "zh-CN"),
+        "购物车中有 1 件商品"
+    );
+}
@@ -0,0 +1,71 @@
+use widerror::*;
+
+#[test]
+fn canonical_names() {
+    assert_eq!(Kind::NotFound.as_str(), "NOT_FOUND");
+    assert_eq!(Scope::Clientside.to_string(), "CLIENTSIDE");
+    assert_eq!(format!("{:#}", Scope::Clientside), "1");
+    assert_eq!(
+        format!("{} {:#}", Kind::Unauthenticated, Kind::Unauthenticated),
+        "UNAUTHENTICATED 16"
+    );
+    assert_eq!("ALLOWED".parse::<RetryMode>(), Ok(RetryMode::Allowed));
+    assert_eq!(
+        "never".parse::<PassThroughMode>(),
+        Ok(PassThroughMode::Never)
+    );
+    assert!("MISSING".parse::<Kind>().is_err());
+    assert_eq!(Kind::try_from(14), Ok(Kind::Unavailable));
+    assert!(Scope::try_from(3).is_err());
+
+    for kind in Kind::ALL {
+        assert_eq!(kind.as_str().parse::<Kind>(), Ok(*kind));
+        assert_eq!(Kind::try_from(*kind as i8), Ok(*kind));
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct Log {
+    #[serde(with = "widerror::serde_names")]
+    kind: Kind,
+    #[serde(with = "widerror::serde_names")]
+    retry_mode: RetryMode,
+}
+
+#[test]
+fn serde_helpers() {
+    let log = Log {
+        kind: Kind::NotFound,
+        retry_mode: RetryMode::Denied,
+    };
+    let json = serde_json::to_string(&log).unwrap();
+    assert_eq!(json, r#"{"kind":"NOT_FOUND","retry_mode":"DENIED"}"#);
+    assert_eq!(serde_json::from_str::<Log>(&json).unwrap(), log);
+    assert_eq!(
+        serde_json::from_str::<Log>(r#"{"kind":5,"retry_mode":2}"#).unwrap(),
+        log
+    );
+    assert!(serde_json::from_str::<Log>(r#"{"kind":99,"retry_mode":2}"#).is_err());
+}
+
+#[test]
+fn wid_error_wire_format() {
+    let mut err = WidError::new(ErrorCode::new(100010001), Message::default());
+    err.kind = Kind::NotFound;
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["kind"], 5);
+    assert_eq!(
+        serde_json::from_value::<WidError>(json).unwrap().kind,
+        Kind::NotFound
+    );
+}
+
+#[test]
+fn named_wrapper() {
+    let names = vec![Named(Scope::Clientside), Named(Scope::Internal)];
+    let json = serde_json::to_string(&names).unwrap();
+    assert_eq!(json, r#"["CLIENTSIDE","INTERNAL"]"#);
+    let back: Vec<Named<Scope>> = serde_json::from_str(r#"["clientside",0]"#).unwrap();
+    assert_eq!(back, names);
+    assert_eq!(Named::from(Kind::Aborted).0, Kind::Aborted);
+}
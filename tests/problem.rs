@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use serde_json::json;
+use widerror::details::RetryInfo;
+use widerror::problem::*;
+use widerror::*;
+
+fn sample() -> WidError {
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("order 7 not found".into()),
+    )
+    .with_detail(RetryInfo {
+        retry_delay: Duration::from_secs(2),
+    });
+    err.name = "ORDER_NOT_FOUND".into();
+    err.kind = Kind::NotFound;
+    err.retry_mode = RetryMode::Denied;
+    err
+}
+
+#[test]
+fn to_problem_document() {
+    let config = ProblemConfig::new("https://errors.example.com/");
+    let problem = sample().to_problem(&config).with_instance("/orders/7");
+    assert_eq!(
+        serde_json::to_value(&problem).unwrap(),
+        json!({
+            "type": "https://errors.example.com/10001/#ORDER_NOT_FOUND",
+            "title": "ORDER_NOT_FOUND",
+            "status": 404,
+            "detail": "order 7 not found",
+            "instance": "/orders/7",
+            "code": 100010001,
+            "kind": "NOT_FOUND",
+            "retry_mode": "DENIED",
+            "details": [{"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "2s"}]
+        })
+    );
+
+    let blank = sample().to_problem(&ProblemConfig::default());
+    assert_eq!(blank.type_uri, ABOUT_BLANK);
+}
+
+#[test]
+fn help_links_to_the_type_uri() {
+    let config = ProblemConfig::new("https://errors.example.com");
+    let err = sample();
+    let help = config.help(&err).unwrap();
+    assert_eq!(help.links[0].url, err.to_problem(&config).type_uri);
+    assert_eq!(help.links[0].description, "Reference of ORDER_NOT_FOUND");
+    assert!(ProblemConfig::default().help(&err).is_none());
+}
+
+#[test]
+fn round_trip() {
+    let config = ProblemConfig::new("https://errors.example.com");
+    let err = WidError::from(sample().to_problem(&config));
+    assert_eq!(err.code, 100010001);
+    assert_eq!(err.namespace, 10001);
+    assert_eq!(err.name, "ORDER_NOT_FOUND");
+    assert_eq!(err.kind, Kind::NotFound);
+    assert_eq!(err.retry_mode, RetryMode::Denied);
+    assert_eq!(err.mapping_code, 404);
+    assert_eq!(err.to_string(), "[NOT_FOUND 100010001] order 7 not found");
+    assert_eq!(
+        err.detail::<RetryInfo>().unwrap().retry_delay,
+        Duration::from_secs(2)
+    );
+}
+
+#[test]
+fn third_party_problem() {
+    let problem: Problem = serde_json::from_value(json!({
+        "type": "https://example.com/probs/out-of-credit",
+        "title": "You do not have enough credit.",
+        "status": 403,
+        "detail": "Your current balance is 30, but that costs 50.",
+        "balance": 30
+    }))
+    .unwrap();
+    assert_eq!(problem.extensions["balance"], 30);
+    let err = problem.to_error();
+    assert!(err.code.is_none());
+    assert_eq!(err.kind, Kind::PermissionDenied);
+    assert_eq!(err.mapping_code, 403);
+    assert_eq!(
+        err.message.to_string(),
+        "Your current balance is 30, but that costs 50."
+    );
+
+    let minimal: Problem = serde_json::from_value(json!({"title": "Conflict"})).unwrap();
+    assert_eq!(minimal.type_uri, ABOUT_BLANK);
+    let err = minimal.to_error();
+    assert_eq!(err.kind, Kind::Internal);
+    assert_eq!(err.message.to_string(), "Conflict");
+}
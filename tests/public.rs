@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use widerror::details::*;
+use widerror::*;
+
+fn error(mode: PassThroughMode, scope: Scope, kind: Kind) -> WidError {
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("order 7 not found".into()),
+    );
+    err.name = "ORDER_NOT_FOUND".into();
+    err.pass_through_mode = mode;
+    err.scope = scope;
+    err.kind = kind;
+    err.retry_mode = RetryMode::Allowed;
+    err
+}
+
+fn fixed_id() -> String {
+    "corr-1".into()
+}
+
+fn policy() -> PublicPolicy {
+    PublicPolicy {
+        correlation_id: fixed_id,
+        ..PublicPolicy::default()
+    }
+}
+
+fn assert_generic(public: &WidError) {
+    assert_eq!(public.kind, Kind::Internal);
+    assert!(public.code.is_none());
+    assert_eq!(public.name, "INTERNAL");
+    assert!(matches!(&public.message, Message::Default(t) if t.text == "internal error"));
+    assert_eq!(public.detail::<RequestInfo>().unwrap().request_id, "corr-1");
+    assert_eq!(public.retry_mode, RetryMode::Allowed);
+}
+
+#[test]
+fn every_mode_and_scope() {
+    use PassThroughMode::*;
+    let policy = policy();
+    for (mode, scope, kind, shown) in [
+        (Never, Scope::Clientside, Kind::NotFound, false),
+        (Never, Scope::Serverside, Kind::NotFound, false),
+        (Never, Scope::Internal, Kind::NotFound, false),
+        (Should, Scope::Clientside, Kind::Internal, true),
+        (Should, Scope::Serverside, Kind::NotFound, true),
+        (Should, Scope::Internal, Kind::DataLoss, true),
+        (Auto, Scope::Clientside, Kind::NotFound, true),
+        (Auto, Scope::Serverside, Kind::Unavailable, true),
+        (Auto, Scope::Internal, Kind::NotFound, false),
+        (Auto, Scope::Clientside, Kind::Internal, false),
+        (Auto, Scope::Serverside, Kind::Unknown, false),
+        (Auto, Scope::Serverside, Kind::DataLoss, false),
+    ] {
+        let err = error(mode, scope, kind);
+        let public = err.to_public(&policy);
+        assert_eq!(
+            policy.is_public(&err),
+            shown,
+            "{:?} {:?} {:?}",
+            mode,
+            scope,
+            kind
+        );
+        if shown {
+            assert_eq!(public.code, err.code);
+            assert_eq!(public.name, err.name);
+            assert_eq!(public.kind, kind);
+        } else {
+            assert_generic(&public);
+        }
+    }
+}
+
+#[test]
+fn configurable_auto_rules() {
+    let policy = PublicPolicy {
+        pass_scopes: vec![Scope::Clientside],
+        hidden_kinds: vec![],
+        ..policy()
+    };
+    let serverside = error(PassThroughMode::Auto, Scope::Serverside, Kind::Unavailable);
+    assert_generic(&serverside.to_public(&policy));
+    let internal_kind = error(PassThroughMode::Auto, Scope::Clientside, Kind::Internal);
+    assert_eq!(internal_kind.to_public(&policy).name, "ORDER_NOT_FOUND");
+}
+
+#[test]
+fn existing_request_id_is_kept() {
+    let err =
+        error(PassThroughMode::Never, Scope::Internal, Kind::Internal).with_detail(RequestInfo {
+            request_id: "req-42".into(),
+            serving_data: "db timeout on shard 3".into(),
+        });
+    let public = err.to_public(&policy());
+    let info = public.detail::<RequestInfo>().unwrap();
+    assert_eq!(info.request_id, "req-42");
+    assert!(info.serving_data.is_empty());
+
+    let generated = error(PassThroughMode::Never, Scope::Internal, Kind::Internal)
+        .to_public(&PublicPolicy::default());
+    assert_eq!(
+        generated.detail::<RequestInfo>().unwrap().request_id.len(),
+        16
+    );
+}
+
+#[test]
+fn strips_sources_and_sensitive_details() {
+    let cause = error(
+        PassThroughMode::Should,
+        Scope::Serverside,
+        Kind::Unavailable,
+    );
+    let hidden = error(PassThroughMode::Never, Scope::Internal, Kind::Internal);
+    let err = error(PassThroughMode::Should, Scope::Clientside, Kind::NotFound)
+        .with_detail(DebugInfo {
+            stack_entries: vec!["main.rs:1".into()],
+            detail: "sql: select ...".into(),
+        })
+        .with_detail(ErrorInfo {
+            reason: "ORDER_NOT_FOUND".into(),
+            domain: "orders".into(),
+            metadata: BTreeMap::from([
+                ("order_id".into(), "7".into()),
+                ("db_host".into(), "10.0.0.3".into()),
+            ]),
+        })
+        .with_source(cause.with_source(hidden));
+
+    let public = err.to_public(&policy());
+    assert!(public.detail::<DebugInfo>().is_none());
+    assert!(public.source_wid().is_none());
+    assert!(public.location().is_none());
+
+    let policy = PublicPolicy {
+        keep_sources: true,
+        sensitive_metadata: vec!["db_host".into()],
+        ..policy()
+    };
+    let public = err.to_public(&policy);
+    let metadata = public.detail::<ErrorInfo>().unwrap().metadata;
+    assert_eq!(metadata.keys().collect::<Vec<_>>(), ["order_id"]);
+    let source = public.source_wid().unwrap();
+    assert_eq!(source.kind, Kind::Unavailable);
+    assert!(source.source_wid().is_none());
+}
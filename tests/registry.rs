@@ -0,0 +1,123 @@
+use widerror::registry::{self, ErrorDef, Registry, RegistryError};
+use widerror::{ErrorCode, Kind, Namespace};
+
+const ORDER: Namespace = Namespace::new(10001);
+const ORDER_NOT_FOUND: ErrorDef = ErrorDef::new(
+    ErrorCode::with_namespace(100010001, ORDER),
+    "ORDER_NOT_FOUND",
+    ORDER,
+    Kind::NotFound,
+    "order not found",
+);
+const ORDER_EXISTS: ErrorDef = ErrorDef::new(
+    ErrorCode::from_parts(ORDER, 2),
+    "ORDER_EXISTS",
+    ORDER,
+    Kind::AlreadyExists,
+    "order exists",
+);
+
+#[cfg(feature = "inventory")]
+widerror::register_error!(ErrorDef::new(
+    ErrorCode::new(100020001),
+    "LINKED_ERROR",
+    Namespace::new(10002),
+    Kind::Internal,
+    "registered at link time",
+));
+
+fn def(code: u32, name: &'static str, namespace: u32) -> ErrorDef {
+    ErrorDef::new(
+        ErrorCode::new(code),
+        name,
+        Namespace::new(namespace),
+        Kind::Internal,
+        "",
+    )
+}
+
+#[test]
+fn lookup() {
+    let registry = Registry::from_defs([ORDER_NOT_FOUND, ORDER_EXISTS]).unwrap();
+    assert_eq!(registry.len(), 2);
+    assert_eq!(
+        registry.by_code(ErrorCode::new(100010002)).unwrap().name,
+        "ORDER_EXISTS"
+    );
+    assert_eq!(
+        registry.by_name("ORDER_NOT_FOUND").unwrap().kind,
+        Kind::NotFound
+    );
+    assert!(registry.by_code(ErrorCode::new(100010003)).is_none());
+
+    let err = registry.by_name("ORDER_NOT_FOUND").unwrap().to_error();
+    assert_eq!(err.code, 100010001);
+    assert_eq!(err.namespace, 10001);
+    assert_eq!(err.kind, Kind::NotFound);
+}
+
+#[test]
+fn rejects_conflicts() {
+    let errors = Registry::from_defs([
+        ORDER_NOT_FOUND,
+        def(100010001, "ORDER_GONE", 10001),
+        def(100010003, "ORDER_NOT_FOUND", 10001),
+        def(100020004, "WRONG_NAMESPACE", 10001),
+        def(0, "NO_CODE", 10001),
+        def(100010005, "NO_NAMESPACE", 0),
+    ])
+    .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            RegistryError::DuplicateCode {
+                code: ErrorCode::new(100010001),
+                existing: "ORDER_NOT_FOUND".into(),
+                rejected: "ORDER_GONE".into()
+            },
+            RegistryError::DuplicateName {
+                name: "ORDER_NOT_FOUND".into(),
+                existing: ErrorCode::new(100010001),
+                rejected: ErrorCode::new(100010003)
+            },
+            RegistryError::NamespaceMismatch {
+                code: ErrorCode::new(100020004),
+                namespace: ORDER
+            },
+            RegistryError::InvalidCode {
+                code: ErrorCode::NONE
+            },
+            RegistryError::InvalidNamespace {
+                code: ErrorCode::new(100010005),
+                namespace: Namespace::NONE
+            },
+        ]
+    );
+}
+
+#[test]
+fn export_round_trip() {
+    let registry = Registry::from_defs([ORDER_EXISTS, ORDER_NOT_FOUND]).unwrap();
+    let json = serde_json::to_string(&registry).unwrap();
+    let back: Registry = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        back.iter().map(|d| d.code.get()).collect::<Vec<_>>(),
+        vec![100010001, 100010002]
+    );
+
+    let duplicated = format!("[{0},{0}]", serde_json::to_string(&ORDER_EXISTS).unwrap());
+    assert!(serde_json::from_str::<Registry>(&duplicated).is_err());
+}
+
+#[test]
+fn global_registry() {
+    registry::init().unwrap();
+    registry::register(def(100030001, "GLOBAL_ERROR", 10003)).unwrap();
+    assert!(registry::register(def(100030001, "GLOBAL_AGAIN", 10003)).is_err());
+    assert_eq!(
+        registry::by_code(ErrorCode::new(100030001)).unwrap().name,
+        "GLOBAL_ERROR"
+    );
+    #[cfg(feature = "inventory")]
+    assert_eq!(registry::by_name("LINKED_ERROR").unwrap().code, 100020001);
+}
@@ -0,0 +1,75 @@
+use std::io;
+
+use widerror::details::{Help, Link};
+use widerror::*;
+
+fn wid(code: u32, kind: Kind, text: &str) -> WidError {
+    let mut err = WidError::new(ErrorCode::new(code), Message::Default(text.into()));
+    err.kind = kind;
+    err
+}
+
+fn sample() -> WidError {
+    wid(100010001, Kind::NotFound, "order 7 not found").with_source(
+        wid(100020003, Kind::Unavailable, "stock service down")
+            .with_source(io::Error::other("connection reset")),
+    )
+}
+
+#[test]
+fn compact_and_inline() {
+    let err = sample();
+    assert_eq!(err.to_string(), "[NOT_FOUND 100010001] order 7 not found");
+    assert_eq!(
+        format!("{:#}", err),
+        "[NOT_FOUND 100010001] order 7 not found: \
+         [UNAVAILABLE 100020003] stock service down: connection reset"
+    );
+    assert_eq!(WidError::default().to_string(), "[OK] ");
+}
+
+#[test]
+fn multiline_report() {
+    let err = sample().with_detail(Help {
+        links: vec![Link {
+            description: "Order errors".into(),
+            url: "https://docs.example.com/orders".into(),
+        }],
+    });
+    let report = format!("{:?}", err);
+    let location = err.location().unwrap();
+    assert_eq!(
+        report,
+        format!(
+            "[NOT_FOUND 100010001] order 7 not found\n\
+             \n\
+             Caused by:\n    \
+             0: [UNAVAILABLE 100020003] stock service down\n    \
+             1: connection reset\n\
+             \n\
+             at {}\n\
+             \n\
+             Help:\n    \
+             Order errors: https://docs.example.com/orders",
+            location
+        )
+    );
+    assert_eq!(err.report().to_string(), report);
+    assert!(format!("{:#?}", err).starts_with("WidError {\n    message:"));
+}
+
+#[test]
+fn explicit_styles() {
+    let err = sample();
+    assert_eq!(
+        err.report().style(ReportStyle::Compact).to_string(),
+        err.to_string()
+    );
+    assert_eq!(
+        err.report().style(ReportStyle::Inline).to_string(),
+        format!("{:#}", err)
+    );
+    let colored = err.report().style(ReportStyle::Color).to_string();
+    assert!(colored.starts_with("\x1b[1;31m[NOT_FOUND 100010001]"));
+    assert!(colored.contains("Caused by:"));
+}
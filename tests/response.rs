@@ -0,0 +1,161 @@
+#![cfg(any(feature = "axum", feature = "actix-web"))]
+
+use std::time::Duration;
+
+use serde_json::Value;
+use widerror::details::{ErrorDetail, RetryInfo};
+use widerror::response::*;
+use widerror::*;
+
+fn error(kind: Kind, mode: PassThroughMode) -> WidError {
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("something failed".into()),
+    );
+    err.name = "SOMETHING_FAILED".into();
+    err.kind = kind;
+    err.scope = Scope::Clientside;
+    err.pass_through_mode = mode;
+    err
+}
+
+fn header<'a>(parts: &'a ResponseParts, name: &str) -> Option<&'a str> {
+    parts
+        .headers
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+#[test]
+fn json_body_hides_fields() {
+    let parts = Exposure::default().response_parts(&error(Kind::NotFound, PassThroughMode::Auto));
+    assert_eq!(parts.status, 404);
+    assert_eq!(header(&parts, "content-type"), Some("application/json"));
+    let body: Value = serde_json::from_slice(&parts.body).unwrap();
+    assert_eq!(body["code"], 100010001);
+    assert_eq!(body["name"], "SOMETHING_FAILED");
+    assert!(body.get("level").is_none());
+    assert!(body.get("mapping_code").is_none());
+    assert_eq!(header(&parts, "retry-after"), None);
+}
+
+#[test]
+fn hidden_errors_become_internal() {
+    let parts = Exposure::default().response_parts(&error(Kind::NotFound, PassThroughMode::Never));
+    assert_eq!(parts.status, 500);
+    let body: Value = serde_json::from_slice(&parts.body).unwrap();
+    assert_eq!(body["name"], "INTERNAL");
+    assert!(body["details"][0]["requestId"].is_string());
+}
+
+#[test]
+fn retry_after_and_www_authenticate() {
+    let exposure = Exposure::default();
+    let err = error(Kind::Unavailable, PassThroughMode::Auto);
+    assert_eq!(
+        header(&exposure.response_parts(&err), "retry-after"),
+        Some("1")
+    );
+    let err = err.with_detail(RetryInfo {
+        retry_delay: Duration::from_millis(2500),
+    });
+    assert_eq!(
+        header(&exposure.response_parts(&err), "retry-after"),
+        Some("3")
+    );
+
+    let exposure = Exposure {
+        www_authenticate: r#"Bearer realm="api""#.into(),
+        ..Exposure::default()
+    };
+    let parts = exposure.response_parts(&error(Kind::Unauthenticated, PassThroughMode::Auto));
+    assert_eq!(parts.status, 401);
+    assert_eq!(
+        header(&parts, "www-authenticate"),
+        Some(r#"Bearer realm="api""#)
+    );
+}
+
+#[test]
+fn retry_after_follows_the_public_error() {
+    // Hidden as internal: the generic error is not retryable.
+    let mut err = error(Kind::Unavailable, PassThroughMode::Auto);
+    err.scope = Scope::Internal;
+    let err = err.with_detail(RetryInfo {
+        retry_delay: Duration::from_secs(30),
+    });
+    let parts = Exposure::default().response_parts(&err);
+    assert_eq!(parts.status, 500);
+    assert_eq!(header(&parts, "retry-after"), None);
+
+    // A stripped `RetryInfo` does not leak its delay.
+    let mut exposure = Exposure::default();
+    exposure
+        .policy
+        .stripped_details
+        .push(RetryInfo::TYPE_URL.to_string());
+    let err = error(Kind::Unavailable, PassThroughMode::Auto).with_detail(RetryInfo {
+        retry_delay: Duration::from_secs(30),
+    });
+    assert_eq!(
+        header(&exposure.response_parts(&err), "retry-after"),
+        Some("1")
+    );
+
+    exposure.retry.retryable_kinds.clear();
+    assert_eq!(header(&exposure.response_parts(&err), "retry-after"), None);
+}
+
+#[test]
+fn retry_after_is_capped() {
+    let err = error(Kind::Unavailable, PassThroughMode::Auto).with_detail(RetryInfo {
+        retry_delay: Duration::MAX,
+    });
+    let parts = Exposure::default().response_parts(&err);
+    assert_eq!(header(&parts, "retry-after"), Some("86400"));
+
+    let err = error(Kind::Unavailable, PassThroughMode::Auto).with_detail(RetryInfo {
+        retry_delay: Duration::from_millis(2_500),
+    });
+    let parts = Exposure::default().response_parts(&err);
+    assert_eq!(header(&parts, "retry-after"), Some("3"));
+}
+
+#[test]
+fn hidden_fields_of_sources() {
+    let exposure = Exposure {
+        policy: PublicPolicy {
+            keep_sources: true,
+            ..PublicPolicy::default()
+        },
+        ..Exposure::default()
+    };
+    let mut cause = error(Kind::NotFound, PassThroughMode::Auto);
+    cause.mapping_code = 404;
+    let err = error(Kind::NotFound, PassThroughMode::Auto).with_source(cause);
+    let body: Value = serde_json::from_slice(&exposure.response_parts(&err).body).unwrap();
+    assert_eq!(body["source_error"]["name"], "SOMETHING_FAILED");
+    assert!(body["source_error"].get("mapping_code").is_none());
+    assert!(body["source_error"].get("level").is_none());
+}
+
+#[test]
+fn problem_body() {
+    let exposure = Exposure {
+        format: BodyFormat::Problem,
+        problem: widerror::problem::ProblemConfig::new("https://errors.example.com"),
+        ..Exposure::default()
+    };
+    let parts = exposure.response_parts(&error(Kind::NotFound, PassThroughMode::Auto));
+    assert_eq!(
+        header(&parts, "content-type"),
+        Some("application/problem+json")
+    );
+    let body: Value = serde_json::from_slice(&parts.body).unwrap();
+    assert_eq!(
+        body["type"],
+        "https://errors.example.com/10001/#SOMETHING_FAILED"
+    );
+    assert_eq!(body["status"], 404);
+}
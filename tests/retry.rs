@@ -0,0 +1,182 @@
+#![allow(clippy::result_large_err)]
+
+use std::time::Duration;
+
+use widerror::details::RetryInfo;
+use widerror::retry::*;
+use widerror::*;
+
+fn failure(kind: Kind, mode: RetryMode) -> WidError {
+    let mut err = WidError::new(ErrorCode::new(100010001), Message::Default("x".into()));
+    err.kind = kind;
+    err.retry_mode = mode;
+    err
+}
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 5,
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    }
+}
+
+#[test]
+fn exponential_backoff_until_success() {
+    let clock = MockClock::new();
+    let mut calls = 0;
+    let result = retry_with_clock(&policy(), &clock, || {
+        calls += 1;
+        if calls < 4 {
+            Err(failure(Kind::Unavailable, RetryMode::Unknown))
+        } else {
+            Ok(calls)
+        }
+    });
+    assert_eq!(result.unwrap(), 4);
+    assert_eq!(clock.sleeps(), [100, 200, 400].map(Duration::from_millis));
+}
+
+#[test]
+fn retry_mode_overrides_kind() {
+    let policy = policy();
+    assert!(policy.is_retryable(&failure(Kind::Aborted, RetryMode::Unknown)));
+    assert!(!policy.is_retryable(&failure(Kind::NotFound, RetryMode::Unknown)));
+    assert!(policy.is_retryable(&failure(Kind::NotFound, RetryMode::Allowed)));
+    assert!(!policy.is_retryable(&failure(Kind::Unavailable, RetryMode::Denied)));
+
+    let clock = MockClock::new();
+    let mut calls = 0;
+    let err = retry_with_clock(&policy, &clock, || -> Result<(), _> {
+        calls += 1;
+        Err(failure(Kind::Unavailable, RetryMode::Denied))
+    })
+    .unwrap_err();
+    assert_eq!(calls, 1);
+    assert_eq!(err.retry_mode, RetryMode::Denied);
+}
+
+#[test]
+fn caps_and_server_delay() {
+    let clock = MockClock::new();
+    let mut calls = 0;
+    let _ = retry_with_clock(&policy(), &clock, || -> Result<(), _> {
+        calls += 1;
+        Err(
+            failure(Kind::Unavailable, RetryMode::Unknown).with_detail(RetryInfo {
+                retry_delay: Duration::from_secs(3),
+            }),
+        )
+    });
+    assert_eq!(calls, 5);
+    assert_eq!(clock.sleeps(), [Duration::from_secs(3); 4]);
+
+    let policy = RetryPolicy {
+        deadline: Some(Duration::from_millis(500)),
+        max_backoff: Duration::from_millis(250),
+        ..policy()
+    };
+    let clock = MockClock::new();
+    calls = 0;
+    let _ = retry_with_clock(&policy, &clock, || -> Result<(), _> {
+        calls += 1;
+        Err(failure(Kind::Unavailable, RetryMode::Unknown))
+    });
+    assert_eq!(calls, 3);
+    assert_eq!(clock.sleeps(), [100, 200].map(Duration::from_millis));
+}
+
+#[test]
+fn huge_server_delay_is_capped() {
+    let err = failure(Kind::Unavailable, RetryMode::Unknown).with_detail(RetryInfo {
+        retry_delay: Duration::MAX,
+    });
+    assert_eq!(
+        policy().next_delay(1, Duration::ZERO, &err),
+        Some(Duration::from_secs(10))
+    );
+
+    let policy = RetryPolicy {
+        deadline: Some(Duration::MAX),
+        max_backoff: Duration::MAX,
+        ..policy()
+    };
+    assert_eq!(policy.next_delay(1, Duration::from_secs(1), &err), None);
+    assert_eq!(
+        policy.next_delay(1, Duration::ZERO, &err),
+        Some(Duration::MAX)
+    );
+}
+
+#[test]
+fn jitter_stays_within_bounds() {
+    let policy = RetryPolicy {
+        jitter: 0.5,
+        ..policy()
+    };
+    let err = failure(Kind::Unavailable, RetryMode::Unknown);
+    for _ in 0..100 {
+        let delay = policy.next_delay(2, Duration::ZERO, &err).unwrap();
+        assert!(delay > Duration::from_millis(100) && delay <= Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn backoff_saturates_at_max() {
+    let policy = RetryPolicy {
+        max_attempts: 200,
+        ..policy()
+    };
+    let clock = MockClock::new();
+    let _ = retry_with_clock(&policy, &clock, || -> Result<(), _> {
+        Err(failure(Kind::Unavailable, RetryMode::Unknown))
+    });
+    let sleeps = clock.sleeps();
+    assert_eq!(sleeps.len(), 199);
+    assert!(sleeps[20..]
+        .iter()
+        .all(|&delay| delay == policy.max_backoff));
+
+    let err = failure(Kind::Unavailable, RetryMode::Unknown);
+    for multiplier in [f64::NAN, f64::INFINITY, -2.0] {
+        let policy = RetryPolicy {
+            multiplier,
+            max_attempts: u32::MAX,
+            ..policy.clone()
+        };
+        assert_eq!(
+            policy.validate().unwrap_err().to_string(),
+            format!("retry policy multiplier cannot be {}", multiplier)
+        );
+        let delay = policy.next_delay(u32::MAX - 1, Duration::ZERO, &err);
+        assert_eq!(delay, Some(policy.initial_backoff));
+    }
+    let policy = RetryPolicy {
+        jitter: f64::NAN,
+        ..policy
+    };
+    assert_eq!(policy.validate().unwrap_err().field, "jitter");
+    assert!(policy.next_delay(1, Duration::ZERO, &err).is_some());
+    assert!(RetryPolicy::default().validate().is_ok());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn async_variant() {
+    let clock = MockClock::new();
+    let mut calls = 0;
+    let result = retry_async_with_clock(&policy(), &clock, || {
+        calls += 1;
+        let calls = calls;
+        async move {
+            if calls < 3 {
+                Err(failure(Kind::Aborted, RetryMode::Unknown))
+            } else {
+                Ok(calls)
+            }
+        }
+    })
+    .await;
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(clock.elapsed(), Duration::from_millis(300));
+}
@@ -0,0 +1,92 @@
+use widerror::*;
+
+#[test]
+fn bands() {
+    assert_eq!(Severity::from_level(0), Severity::Error);
+    assert_eq!(Severity::from_level(1), Severity::Debug);
+    assert_eq!(Severity::from_level(31), Severity::Debug);
+    assert_eq!(Severity::from_level(128), Severity::Error);
+    assert_eq!(Severity::from_level(255), Severity::Emergency);
+    for &severity in Severity::ALL {
+        assert_eq!(Severity::from_level(severity.level()), severity);
+        assert_eq!(
+            Severity::from_syslog_code(severity.syslog_code()),
+            Some(severity)
+        );
+    }
+    assert_eq!(Severity::Error.syslog_code(), 3);
+    assert_eq!(
+        format!("{} {:#}", Severity::Warning, Severity::Warning),
+        "WARNING 4"
+    );
+    assert_eq!("critical".parse::<Severity>().unwrap(), Severity::Critical);
+}
+
+#[test]
+fn numbers_are_syslog_codes() {
+    for &severity in Severity::ALL {
+        let number = format!("{:#}", severity);
+        let code: i8 = number.parse().unwrap();
+        assert_eq!(Severity::try_from(code), Ok(severity));
+
+        let json = serde_json::to_string(&Named(severity)).unwrap();
+        assert_eq!(json, format!("\"{}\"", severity));
+        let named: Named<Severity> = serde_json::from_str(&number).unwrap();
+        assert_eq!(named.0, severity);
+    }
+    assert_eq!(Severity::try_from(3), Ok(Severity::Error));
+    assert_eq!(Severity::try_from(7), Ok(Severity::Debug));
+    assert!(Severity::try_from(8).is_err());
+}
+
+#[test]
+fn unset_level() {
+    let err = WidError::default();
+    assert_eq!(err.level, 0);
+    assert_eq!(err.severity(), Severity::Error);
+    assert_eq!(err.severity(), Severity::default());
+
+    let debug = WidError::default().with_severity(Severity::Debug);
+    assert_eq!(debug.level, 1);
+    assert_eq!(debug.severity(), Severity::Debug);
+}
+
+#[test]
+fn thresholds() {
+    let err = WidError::default().with_severity(Severity::Critical);
+    assert_eq!(err.level, 160);
+    assert!(err.is_at_least(Severity::Error));
+    assert!(err.is_at_least(Severity::Critical));
+    assert!(!err.is_at_least(Severity::Alert));
+}
+
+#[test]
+fn levels_inside_a_band_round_trip() {
+    let mut err = WidError::default();
+    err.level = 133;
+    assert_eq!(err.severity(), Severity::Error);
+    let back: WidError = serde_json::from_str(&serde_json::to_string(&err).unwrap()).unwrap();
+    assert_eq!(back.level, 133);
+    assert_eq!(back.severity(), Severity::Error);
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn log_levels() {
+    assert_eq!(log::Level::from(Severity::Notice), log::Level::Info);
+    assert_eq!(log::Level::from(Severity::Emergency), log::Level::Error);
+    assert_eq!(Severity::from(log::Level::Trace), Severity::Debug);
+    assert_eq!(Severity::from(log::Level::Warn), Severity::Warning);
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_levels() {
+    assert_eq!(
+        tracing::Level::from(Severity::Warning),
+        tracing::Level::WARN
+    );
+    assert_eq!(tracing::Level::from(Severity::Alert), tracing::Level::ERROR);
+    assert_eq!(Severity::from(tracing::Level::TRACE), Severity::Debug);
+    assert_eq!(Severity::from(tracing::Level::ERROR), Severity::Error);
+}
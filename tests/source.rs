@@ -0,0 +1,69 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+use widerror::*;
+
+#[derive(Debug)]
+struct Outer(io::Error);
+
+impl Display for Outer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("cannot load config")
+    }
+}
+
+impl Error for Outer {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn sample() -> WidError {
+    let io = io::Error::new(io::ErrorKind::NotFound, "config.toml missing");
+    WidError::new(ErrorCode::new(100010001), Message::Default("boot".into())).with_source(Outer(io))
+}
+
+#[test]
+fn downcast_foreign_source() {
+    let err = sample();
+    let source = err.source().unwrap();
+    let outer = source.downcast_ref::<Outer>().unwrap();
+    assert_eq!(outer.0.kind(), io::ErrorKind::NotFound);
+    assert_eq!(source.source().unwrap().to_string(), "config.toml missing");
+}
+
+#[test]
+fn wid_source_stays_wid() {
+    let cause = WidError::new(ErrorCode::new(100020003), Message::Default("empty".into()));
+    let err = WidError::default().with_source(cause);
+    let source = err.source().unwrap().downcast_ref::<WidError>().unwrap();
+    assert_eq!(source.code, 100020003);
+
+    let value = serde_json::to_value(&err).unwrap();
+    assert_eq!(value["source_error"]["code"], 100020003);
+    let back: WidError = serde_json::from_value(value).unwrap();
+    assert!(back.source().unwrap().is::<WidError>());
+}
+
+#[test]
+fn foreign_source_snapshot() {
+    let value = serde_json::to_value(sample()).unwrap();
+    assert_eq!(
+        value["source_error"],
+        serde_json::json!({
+            "type_name": std::any::type_name::<Outer>(),
+            "message": "cannot load config",
+            "chain": ["config.toml missing"],
+        })
+    );
+
+    let back: WidError = serde_json::from_value(value).unwrap();
+    let snapshot = back
+        .source()
+        .unwrap()
+        .downcast_ref::<ForeignError>()
+        .unwrap();
+    assert_eq!(snapshot.message, "cannot load config");
+    assert_eq!(snapshot.chain, ["config.toml missing"]);
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "tracing")]
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_error::ErrorLayer;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+use widerror::*;
+
+type Fields = BTreeMap<String, String>;
+
+/// Collects the fields of events and recorded spans.
+#[derive(Clone, Default)]
+struct Capture {
+    events: Arc<Mutex<Vec<(Level, Fields)>>>,
+    spans: Arc<Mutex<Fields>>,
+}
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl Visit for Visitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name().into(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+}
+
+impl<S: Subscriber> Layer<S> for Capture {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        attrs.record(&mut Visitor(&mut self.spans.lock().unwrap()));
+    }
+
+    fn on_record(&self, _id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        values.record(&mut Visitor(&mut self.spans.lock().unwrap()));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        self.events
+            .lock()
+            .unwrap()
+            .push((*event.metadata().level(), fields));
+    }
+}
+
+fn sample() -> WidError {
+    let mut cause = WidError::new(
+        ErrorCode::new(100020003),
+        Message::Default("stock empty".into()),
+    );
+    cause.name = "STOCK_EMPTY".into();
+    cause.kind = Kind::Unavailable;
+    let mut err = WidError::new(
+        ErrorCode::new(100010001),
+        Message::Default("order failed".into()),
+    )
+    .with_source(cause)
+    .with_severity(Severity::Warning);
+    err.name = "ORDER_FAILED".into();
+    err.kind = Kind::Aborted;
+    err
+}
+
+#[test]
+fn emit_structured_event() {
+    let capture = Capture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    tracing::subscriber::with_default(subscriber, || sample().emit());
+
+    let events = capture.events.lock().unwrap();
+    let (level, fields) = &events[0];
+    assert_eq!(*level, Level::WARN);
+    assert_eq!(fields["message"], "order failed");
+    assert_eq!(fields["error.code"], "100010001");
+    assert_eq!(fields["error.namespace"], "10001");
+    assert_eq!(fields["error.kind"], "ABORTED");
+    assert_eq!(fields["error.severity"], "WARNING");
+    let chain: serde_json::Value = serde_json::from_str(&fields["error.chain"]).unwrap();
+    assert_eq!(chain[0]["name"], "STOCK_EMPTY");
+    assert_eq!(chain[0]["kind"], "UNAVAILABLE");
+}
+
+#[test]
+fn emitted_at_the_severity_field() {
+    let capture = Capture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    tracing::subscriber::with_default(subscriber, || {
+        WidError::new(ErrorCode::new(100010001), Message::default()).emit();
+        sample().with_severity(Severity::Debug).emit();
+        let mut debug = sample();
+        debug.level = 31;
+        debug.emit();
+    });
+
+    let events = capture.events.lock().unwrap();
+    let emitted: Vec<(Level, &str)> = events
+        .iter()
+        .map(|(level, fields)| (*level, fields["error.severity"].as_str()))
+        .collect();
+    assert_eq!(
+        emitted,
+        [
+            (Level::ERROR, "ERROR"),
+            (Level::DEBUG, "DEBUG"),
+            (Level::DEBUG, "DEBUG")
+        ]
+    );
+}
+
+#[test]
+fn record_on_span() {
+    let capture = Capture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!(
+            "call",
+            error.code = tracing::field::Empty,
+            error.kind = tracing::field::Empty,
+            error.retry_mode = tracing::field::Empty,
+        );
+        sample().record(&span);
+    });
+
+    let spans = capture.spans.lock().unwrap();
+    assert_eq!(spans["error.code"], "100010001");
+    assert_eq!(spans["error.kind"], "ABORTED");
+    assert_eq!(spans["error.retry_mode"], "UNKNOWN");
+    assert!(!spans.contains_key("error.name"));
+    assert!(widerror::trace::FIELDS.contains(&"error.chain"));
+}
+
+#[test]
+fn span_trace_capture() {
+    assert!(WidError::default().span_trace().is_none());
+    assert!(sample().span_trace().is_none());
+
+    let subscriber = tracing_subscriber::registry().with(ErrorLayer::default());
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("checkout");
+        let _enter = span.enter();
+        let err = sample();
+        let trace = err.span_trace().unwrap();
+        assert!(trace.to_string().contains("checkout"));
+    });
+}
@@ -0,0 +1,492 @@
+//! `widerror`: explains, lists and checks error codes from catalog files and
+//! registry exports, decodes serialized errors and generates code and
+//! reference pages.
+
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use widerror::catalog::codegen::Generator;
+use widerror::catalog::diff::diff;
+use widerror::catalog::docs::{DocFormat, DocSite};
+use widerror::catalog::Catalog;
+use widerror::i18n::{BundleCatalog, MessageCatalog};
+use widerror::problem::ProblemConfig;
+use widerror::registry::ErrorDef;
+use widerror::{ForeignError, Namespace, Severity, WidError};
+
+mod sources;
+
+use sources::Sources;
+
+#[derive(Parser)]
+#[command(
+    name = "widerror",
+    version,
+    about = "Explain, list and check WidError codes"
+)]
+struct Cli {
+    /// Catalog file (TOML); may be repeated.
+    #[arg(
+        short,
+        long = "catalog",
+        global = true,
+        env = "WIDERROR_CATALOG",
+        value_delimiter = ','
+    )]
+    catalogs: Vec<PathBuf>,
+    /// Registry export (a JSON list of definitions); may be repeated.
+    #[arg(short, long = "registry", global = true)]
+    registries: Vec<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Describes an error given its code or name.
+    Explain { code: String },
+    /// Lists the known errors in code order.
+    List {
+        /// Only errors of this 5-digit namespace.
+        #[arg(short, long)]
+        namespace: Option<u32>,
+    },
+    /// Validates the catalogs and exports, failing on any violation.
+    Check,
+    /// Pretty-prints a serialized WidError with its sources; reads stdin when
+    /// the argument is missing or `-`.
+    Decode { json: Option<String> },
+    /// Compares two versions of a catalog, failing on breaking changes.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Prints the report as JSON.
+        #[arg(long)]
+        json: bool,
+        /// Succeeds even when there are breaking changes.
+        #[arg(long)]
+        allow_breaking: bool,
+    },
+    /// Renders the catalogs as constants of another language.
+    Generate {
+        language: Language,
+        /// Template replacing the built-in one.
+        #[arg(short, long)]
+        template: Option<PathBuf>,
+        /// Package or module of the generated code.
+        #[arg(short, long)]
+        package: Option<String>,
+        /// Writes to this file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Writes a static reference of the errors, a page per namespace.
+    Docs {
+        /// Directory of the site.
+        #[arg(short, long, default_value = "errors")]
+        output: PathBuf,
+        #[arg(short, long, value_enum, default_value_t = Format::Html)]
+        format: Format,
+        /// Where the site is published; problem type URIs are
+        /// `{base}/{namespace}/#{name}`.
+        #[arg(long)]
+        base: Option<String>,
+        /// Directory of `<locale>.toml` message bundles.
+        #[arg(long)]
+        messages: Option<PathBuf>,
+        /// Locales to list; all the bundles by default. May be repeated.
+        #[arg(long = "locale", value_delimiter = ',')]
+        locales: Vec<String>,
+        /// Heading of the index.
+        #[arg(long, default_value = "Error reference")]
+        title: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Html,
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Language {
+    Typescript,
+    Go,
+    Kotlin,
+    Java,
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let sources = || Sources::load(&cli.catalogs, &cli.registries);
+    let result = match &cli.command {
+        Command::Explain { code } => explain(&sources(), code),
+        Command::List { namespace } => list(&sources(), *namespace),
+        Command::Check => check(&cli, &sources()),
+        Command::Decode { json } => decode(json.as_deref()),
+        Command::Diff {
+            old,
+            new,
+            json,
+            allow_breaking,
+        } => compare(old, new, *json, *allow_breaking),
+        Command::Generate {
+            language,
+            template,
+            package,
+            output,
+        } => generate(
+            &cli.catalogs,
+            *language,
+            template.as_deref(),
+            package.as_deref(),
+            output.as_deref(),
+        ),
+        Command::Docs {
+            output,
+            format,
+            base,
+            messages,
+            locales,
+            title,
+        } => docs(
+            &cli,
+            output,
+            *format,
+            base.as_deref(),
+            messages.as_deref(),
+            locales,
+            title,
+        ),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Problems do not stop `explain` and `list`; `check` reports them.
+fn warn(sources: &Sources) {
+    for problem in &sources.problems {
+        eprintln!("warning: {}", problem);
+    }
+}
+
+/// Aligned `label  value` rows.
+fn fields(out: &mut String, rows: &[(&str, String)]) {
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    for (label, value) in rows {
+        let _ = writeln!(out, "  {:width$}  {}", label, value, width = width);
+    }
+}
+
+fn explain(sources: &Sources, query: &str) -> Result<(), String> {
+    warn(sources);
+    let def = sources
+        .find(query)
+        .ok_or_else(|| format!("no known error has the code or name `{}`", query))?;
+    print!("{}", explanation(sources, def));
+    Ok(())
+}
+
+fn explanation(sources: &Sources, def: &ErrorDef) -> String {
+    let mut out = format!("error[{}]: {}\n\n", def.code, def.name);
+    if let Some(description) = &def.description {
+        let _ = writeln!(out, "{}\n", description.trim_end());
+    }
+    let namespace = match sources.namespaces.get(&def.namespace) {
+        Some(name) => format!("{} ({})", def.namespace, name),
+        None => def.namespace.to_string(),
+    };
+    let mut rows = vec![
+        ("namespace", namespace),
+        (
+            "kind",
+            format!("{} (HTTP {})", def.kind, def.kind.http_status()),
+        ),
+        ("scope", def.scope.to_string()),
+        (
+            "severity",
+            format!("{} (level {})", Severity::from_level(def.level), def.level),
+        ),
+        ("retry", def.retry_mode.to_string()),
+        ("pass-through", def.pass_through_mode.to_string()),
+    ];
+    if def.mapping_code != 0 {
+        rows.push(("mapping code", def.mapping_code.to_string()));
+    }
+    rows.push(("message", def.message.to_string()));
+    if let Some(key) = &def.i18n {
+        rows.push(("i18n key", key.to_string()));
+    }
+    if let Some(note) = &def.deprecated {
+        rows.push(("deprecated", note.to_string()));
+    }
+    fields(&mut out, &rows);
+    out
+}
+
+fn list(sources: &Sources, namespace: Option<u32>) -> Result<(), String> {
+    warn(sources);
+    let namespace = namespace
+        .map(|ns| match Namespace::try_new(ns) {
+            Ok(ns) if !ns.is_none() => Ok(ns),
+            _ => Err(format!("namespace {} does not have 5 digits", ns)),
+        })
+        .transpose()?;
+    let defs: Vec<&ErrorDef> = sources
+        .registry
+        .iter()
+        .filter(|def| namespace.is_none_or(|ns| def.namespace == ns))
+        .collect();
+    let name_width = defs.iter().map(|def| def.name.len()).max().unwrap_or(0);
+    let kind_width = defs
+        .iter()
+        .map(|def| def.kind.as_str().len())
+        .max()
+        .unwrap_or(0);
+    for def in defs {
+        println!(
+            "{}  {:name_width$}  {:kind_width$}  {}",
+            def.code,
+            def.name,
+            def.kind.as_str(),
+            def.message,
+            name_width = name_width,
+            kind_width = kind_width,
+        );
+    }
+    Ok(())
+}
+
+fn check(cli: &Cli, sources: &Sources) -> Result<(), String> {
+    if cli.catalogs.is_empty() && cli.registries.is_empty() {
+        return Err("nothing to check, pass --catalog or --registry".to_string());
+    }
+    for problem in &sources.problems {
+        eprintln!("{}", problem);
+    }
+    if !sources.problems.is_empty() {
+        return Err(format!("{} problem(s) found", sources.problems.len()));
+    }
+    let namespaces = sources
+        .registry
+        .iter()
+        .map(|def| def.namespace)
+        .collect::<std::collections::BTreeSet<_>>();
+    println!(
+        "ok: {} error(s) in {} namespace(s)",
+        sources.registry.len(),
+        namespaces.len()
+    );
+    Ok(())
+}
+
+fn decode(json: Option<&str>) -> Result<(), String> {
+    let json = match json {
+        Some(json) if json != "-" => json.to_string(),
+        _ => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| e.to_string())?;
+            input
+        }
+    };
+    let err: WidError =
+        serde_json::from_str(&json).map_err(|e| format!("not a serialized WidError: {}", e))?;
+    print!("{}", decoded(&err));
+    Ok(())
+}
+
+/// The error, every cause including those inside foreign snapshots, its
+/// attributes and details.
+fn decoded(err: &WidError) -> String {
+    let mut out = format!("{}\n", err);
+    let mut causes = Vec::new();
+    for cause in err.chain().skip(1) {
+        if let Some(wid) = cause.downcast_ref::<WidError>() {
+            causes.push(wid.to_string());
+        } else if let Some(foreign) = cause.downcast_ref::<ForeignError>() {
+            causes.push(format!("{}: {}", foreign.type_name, foreign.message));
+            causes.extend(foreign.chain.iter().cloned());
+        } else {
+            causes.push(cause.to_string());
+        }
+    }
+    if !causes.is_empty() {
+        out.push_str("\nCaused by:\n");
+        for (i, cause) in causes.iter().enumerate() {
+            let _ = writeln!(out, "    {}: {}", i, cause);
+        }
+    }
+    out.push('\n');
+    let mut rows = Vec::new();
+    if !err.name.is_empty() {
+        rows.push(("name", err.name.clone()));
+    }
+    if !err.namespace.is_none() {
+        rows.push(("namespace", err.namespace.to_string()));
+    }
+    rows.extend([
+        ("scope", err.scope.to_string()),
+        (
+            "severity",
+            format!("{} (level {})", err.severity(), err.level),
+        ),
+        ("retry", err.retry_mode.to_string()),
+        ("pass-through", err.pass_through_mode.to_string()),
+    ]);
+    if err.mapping_code != 0 {
+        rows.push(("mapping code", err.mapping_code.to_string()));
+    }
+    fields(&mut out, &rows);
+    for detail in err.details.iter() {
+        let json = serde_json::to_string_pretty(detail).unwrap_or_default();
+        let _ = writeln!(out, "\n{}", json);
+    }
+    out
+}
+
+fn compare(old: &Path, new: &Path, json: bool, allow_breaking: bool) -> Result<(), String> {
+    let old = Catalog::load(old).map_err(|e| e.to_string())?;
+    let new = Catalog::load(new).map_err(|e| e.to_string())?;
+    let changes = diff(&old, &new);
+    if json {
+        let report = serde_json::to_string_pretty(&changes).map_err(|e| e.to_string())?;
+        println!("{}", report);
+    } else {
+        println!("{}", changes);
+    }
+    if changes.is_breaking() && !allow_breaking {
+        return Err("the new catalog breaks clients".to_string());
+    }
+    Ok(())
+}
+
+/// The catalogs as one, failing on any invalid file and on codes or names
+/// defined in more than one file, as `check` does.
+fn merged(paths: &[PathBuf]) -> Result<Catalog, String> {
+    if paths.is_empty() {
+        return Err("no catalog given, pass --catalog".to_string());
+    }
+    let mut merged = Catalog::default();
+    for path in paths {
+        let catalog = Catalog::load(path).map_err(|e| e.to_string())?;
+        merged.namespaces.extend(catalog.namespaces);
+    }
+    no_conflicts(&merged)?;
+    Ok(merged)
+}
+
+/// Fails when a code or a name is defined twice, printing each conflict.
+fn no_conflicts(catalog: &Catalog) -> Result<(), String> {
+    match catalog.to_registry() {
+        Ok(_) => Ok(()),
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{}", error);
+            }
+            Err(format!("{} problem(s) found", errors.len()))
+        }
+    }
+}
+
+fn generate(
+    catalogs: &[PathBuf],
+    language: Language,
+    template: Option<&Path>,
+    package: Option<&str>,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let catalog = merged(catalogs)?;
+    let mut generator = match language {
+        Language::Typescript => Generator::typescript(),
+        Language::Go => Generator::go(),
+        Language::Kotlin => Generator::kotlin(),
+        Language::Java => Generator::java(),
+        Language::Json => Generator::json(),
+    };
+    if let Some(path) = template {
+        let template =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        generator = generator.with_template(&template);
+    }
+    if let Some(package) = package {
+        generator = generator.with_package(package);
+    }
+    let code = generator.render(&catalog).map_err(|e| e.to_string())?;
+    match output {
+        Some(path) => std::fs::write(path, code).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => {
+            print!("{}", code);
+            Ok(())
+        }
+    }
+}
+
+fn docs(
+    cli: &Cli,
+    output: &Path,
+    format: Format,
+    base: Option<&str>,
+    messages: Option<&Path>,
+    locales: &[String],
+    title: &str,
+) -> Result<(), String> {
+    let mut catalog = if cli.catalogs.is_empty() {
+        Catalog::default()
+    } else {
+        merged(&cli.catalogs)?
+    };
+    if !cli.registries.is_empty() {
+        let sources = Sources::load(&[], &cli.registries);
+        if let Some(problem) = sources.problems.first() {
+            return Err(problem.clone());
+        }
+        let exported = Catalog::from_defs(sources.registry.iter().cloned());
+        catalog.namespaces.extend(exported.namespaces);
+        no_conflicts(&catalog)?;
+    }
+    if catalog.namespaces.is_empty() {
+        return Err("nothing to document, pass --catalog or --registry".to_string());
+    }
+    let bundles = messages
+        .map(|dir| BundleCatalog::load_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e)))
+        .transpose()?;
+    let locales = match &bundles {
+        Some(bundles) if locales.is_empty() => {
+            let mut all: Vec<String> = bundles.locales().map(String::from).collect();
+            all.sort();
+            all
+        }
+        _ => locales.to_vec(),
+    };
+    let site = DocSite {
+        format: match format {
+            Format::Html => DocFormat::Html,
+            Format::Markdown => DocFormat::Markdown,
+        },
+        title: title.to_string(),
+        problem: base.map(ProblemConfig::new).unwrap_or_default(),
+        locales,
+        ..DocSite::default()
+    };
+    let written = site
+        .write(
+            &catalog,
+            bundles.as_ref().map(|b| b as &dyn MessageCatalog),
+            output,
+        )
+        .map_err(|e| format!("{}: {}", output.display(), e))?;
+    println!("wrote {} page(s) to {}", written.len(), output.display());
+    Ok(())
+}
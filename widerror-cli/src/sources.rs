@@ -0,0 +1,66 @@
+//! Error definitions gathered from catalog files and registry exports.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use widerror::catalog::Catalog;
+use widerror::registry::{ErrorDef, Registry};
+use widerror::Namespace;
+
+#[derive(Default)]
+pub struct Sources {
+    pub registry: Registry,
+    /// Names of the namespaces declared in catalogs.
+    pub namespaces: BTreeMap<Namespace, String>,
+    /// Everything that was refused, one message per line.
+    pub problems: Vec<String>,
+}
+
+impl Sources {
+    /// Loads every file, keeping the valid definitions and recording the
+    /// problems, including conflicts between files.
+    pub fn load(catalogs: &[PathBuf], registries: &[PathBuf]) -> Sources {
+        let mut sources = Sources::default();
+        let mut defs = Vec::new();
+        for path in catalogs {
+            match Catalog::load(path) {
+                Ok(catalog) => {
+                    for ns in catalog.namespaces {
+                        defs.extend(ns.errors);
+                        sources.namespaces.insert(ns.namespace, ns.name);
+                    }
+                }
+                Err(e) => sources
+                    .problems
+                    .extend(e.to_string().lines().map(String::from)),
+            }
+        }
+        for path in registries {
+            let loaded = std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<Vec<ErrorDef>>(&json).map_err(|e| e.to_string())
+                });
+            match loaded {
+                Ok(exported) => defs.extend(exported),
+                Err(e) => sources.problems.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        if let Err(errors) = sources.registry.register_all(defs) {
+            sources
+                .problems
+                .extend(errors.iter().map(ToString::to_string));
+        }
+        sources
+    }
+
+    /// A definition by code or by name.
+    pub fn find(&self, query: &str) -> Option<&ErrorDef> {
+        let by_code = query
+            .parse()
+            .ok()
+            .and_then(|code| widerror::ErrorCode::try_new(code).ok())
+            .and_then(|code| self.registry.by_code(code));
+        by_code.or_else(|| self.registry.by_name(query))
+    }
+}
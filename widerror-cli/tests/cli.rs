@@ -0,0 +1,246 @@
+use std::io::{self, Write};
+use std::process::{Command, Output, Stdio};
+
+use widerror::registry::Registry;
+use widerror::{ErrorCode, Kind, Message, WidError};
+
+const CATALOG: &str = "../tests/fixtures/catalog/errors.toml";
+
+fn widerror(args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_widerror"))
+        .args(args)
+        .env_remove("WIDERROR_CATALOG")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.unwrap_or_default().as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn explain() {
+    let output = widerror(&["-c", CATALOG, "explain", "100010001"], None);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with(
+        "error[100010001]: ORDER_NOT_FOUND\n\nThe order does not exist or was deleted.\n"
+    ));
+    assert!(text.contains("  kind          NOT_FOUND (HTTP 404)\n"));
+    assert!(text.contains("  i18n key      order.not_found\n"));
+
+    let by_name = widerror(&["-c", CATALOG, "explain", "ORDER_NOT_FOUND"], None);
+    assert_eq!(stdout(&by_name), text);
+
+    let unknown = widerror(&["-c", CATALOG, "explain", "100239999"], None);
+    assert!(!unknown.status.success());
+}
+
+#[test]
+fn list_by_namespace() {
+    let output = widerror(&["-c", CATALOG, "list", "--namespace", "10001"], None);
+    assert_eq!(
+        stdout(&output),
+        "100010001  ORDER_NOT_FOUND  NOT_FOUND  order {id} not found\n\
+         100010002  ORDER_LOCKED     ABORTED    order {id} is locked by {owner}\n"
+    );
+}
+
+#[test]
+fn check() {
+    let output = widerror(&["-c", CATALOG, "check"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "ok: 3 error(s) in 2 namespace(s)\n");
+
+    let dir = std::env::temp_dir().join(format!("widerror-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let export = dir.join("registry.json");
+    let catalog = widerror::catalog::Catalog::load(CATALOG).unwrap();
+    let registry: Registry = catalog.to_registry().unwrap();
+    std::fs::write(&export, serde_json::to_string(&registry).unwrap()).unwrap();
+
+    // The export repeats every code of the catalog.
+    let export = export.to_str().unwrap();
+    let output = widerror(&["-c", CATALOG, "-r", export, "check"], None);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("error code 100010001 is used by both ORDER_NOT_FOUND and ORDER_NOT_FOUND")
+    );
+    assert!(stderr.ends_with("error: 3 problem(s) found\n"));
+
+    let output = widerror(&["-r", export, "list"], None);
+    assert_eq!(stdout(&output).lines().count(), 3);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn decode_with_sources() {
+    let io = io::Error::other("connection refused");
+    let mut inner = WidError::new(
+        ErrorCode::new(100020001),
+        Message::Default("stock service unavailable".into()),
+    )
+    .with_source(io);
+    inner.kind = Kind::Unavailable;
+    let mut err = WidError::new(
+        ErrorCode::new(100010002),
+        Message::Default("order 7 is locked".into()),
+    )
+    .with_source(inner);
+    err.name = "ORDER_LOCKED".into();
+    err.kind = Kind::Aborted;
+    let json = serde_json::to_string(&err).unwrap();
+
+    let output = widerror(&["decode", &json], None);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with(
+        "[ABORTED 100010002] order 7 is locked\n\n\
+         Caused by:\n    \
+         0: [UNAVAILABLE 100020001] stock service unavailable\n    \
+         1: std::io::error::Error: connection refused\n\n  \
+         name          ORDER_LOCKED\n"
+    ));
+
+    let piped = widerror(&["decode"], Some(&json));
+    assert_eq!(stdout(&piped), text);
+
+    let garbage = widerror(&["decode", "{"], None);
+    assert!(!garbage.status.success());
+}
+
+#[test]
+fn diff_gates_breaking_changes() {
+    let dir = std::env::temp_dir().join(format!("widerror-cli-diff-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let old = std::fs::read_to_string(CATALOG).unwrap();
+    let new_path = dir.join("errors.toml");
+    std::fs::write(
+        &new_path,
+        old.replace("kind = \"ABORTED\"", "kind = \"FAILED_PRECONDITION\""),
+    )
+    .unwrap();
+    let new_path = new_path.to_str().unwrap();
+
+    let output = widerror(&["diff", CATALOG, new_path], None);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with(
+        "breaking    100010002 ORDER_LOCKED: kind changed from \"ABORTED\" to \"FAILED_PRECONDITION\"\n"
+    ));
+
+    let output = widerror(
+        &["diff", CATALOG, new_path, "--json", "--allow-breaking"],
+        None,
+    );
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["breaking"], true);
+    assert_eq!(report["changes"][0]["field"], "kind");
+
+    let output = widerror(&["diff", CATALOG, CATALOG], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "no changes\n");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn generate() {
+    let output = widerror(
+        &[
+            "-c",
+            CATALOG,
+            "generate",
+            "kotlin",
+            "--package",
+            "com.example.errors",
+        ],
+        None,
+    );
+    assert!(output.status.success());
+    let expected = std::fs::read_to_string("../tests/fixtures/catalog/errors.kt").unwrap();
+    assert_eq!(stdout(&output), expected);
+
+    let dir = std::env::temp_dir().join(format!("widerror-cli-gen-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("codes.mustache");
+    std::fs::write(&template, "{{#errors}}{{code}}={{name}}\n{{/errors}}").unwrap();
+    let out = dir.join("codes.txt");
+    let output = widerror(
+        &[
+            "-c",
+            CATALOG,
+            "generate",
+            "go",
+            "--template",
+            template.to_str().unwrap(),
+            "-o",
+            out.to_str().unwrap(),
+        ],
+        None,
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        "100010001=ORDER_NOT_FOUND\n100010002=ORDER_LOCKED\n100020001=TYPE_UNAVAILABLE\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+
+    let output = widerror(&["generate", "json"], None);
+    assert!(!output.status.success());
+
+    // The same catalog twice defines every code twice.
+    let output = widerror(&["-c", CATALOG, "-c", CATALOG, "generate", "json"], None);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("error code 100010001 is used by both ORDER_NOT_FOUND and ORDER_NOT_FOUND")
+    );
+    assert!(stderr.ends_with("error: 3 problem(s) found\n"));
+}
+
+#[test]
+fn docs() {
+    let dir = std::env::temp_dir().join(format!("widerror-cli-docs-{}", std::process::id()));
+    let output = widerror(
+        &[
+            "-c",
+            CATALOG,
+            "docs",
+            "-o",
+            dir.to_str().unwrap(),
+            "--format",
+            "markdown",
+            "--base",
+            "https://errors.example.com",
+            "--messages",
+            "../tests/fixtures/i18n",
+        ],
+        None,
+    );
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("wrote 3 page(s) to "));
+    let order = std::fs::read_to_string(dir.join("10001/index.md")).unwrap();
+    assert!(order.contains(
+        "| Problem type | <https://errors.example.com/10001/#ORDER_NOT_FOUND> |\n\n\
+         | Locale | Message |\n|---|---|\n\
+         | en | Order not found |\n\
+         | zh | 订单不存在 |\n\
+         | zh-hant | 訂單不存在 |\n"
+    ));
+    std::fs::remove_dir_all(dir).unwrap();
+
+    let output = widerror(&["docs"], None);
+    assert!(!output.status.success());
+}
@@ -0,0 +1,271 @@
+//! `#[derive(WidError)]` for domain error enums.
+//!
+//! Every variant carries a `#[wid(...)]` attribute describing the `WidError`
+//! it converts into:
+//!
+//! ```ignore
+//! #[derive(Debug, WidError)]
+//! #[wid(namespace = 10001, scope = Clientside)]
+//! enum OrderError {
+//!     #[wid(code = 100010001, kind = NotFound, level = 3, retry = Denied, message = "order {id} not found")]
+//!     NotFound { id: u64 },
+//!     #[wid(code = 100010002, kind = Unavailable, retry = Allowed, pass_through = Should)]
+//!     Busy,
+//! }
+//! ```
+//!
+//! `args(id, ...)` also attaches fields as typed message arguments, which is
+//! how `i18n = "key"` messages get their values; tuple fields are `_0`, `_1`...
+//!
+//! The enum-level attribute supplies defaults for all keys except `code`,
+//! `name`, `message`, `i18n` and `args`.
+//!
+//! Codes are checked at compile time: a code that is not 9 digits, or does not
+//! start with its `namespace`, is rejected.
+//!
+//! ```compile_fail
+//! # use widerror::WidError;
+//! #[derive(Debug, WidError)]
+//! enum Mismatch {
+//!     #[wid(code = 100020001, namespace = 10001)]
+//!     Wrong,
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Fields, Ident, LitStr, Result,
+    Variant,
+};
+
+#[proc_macro_derive(WidError, attributes(wid))]
+pub fn derive_wid_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Keys accepted by `#[wid(...)]`.
+#[derive(Default, Clone)]
+struct Attrs {
+    code: Option<Expr>,
+    name: Option<LitStr>,
+    namespace: Option<Expr>,
+    kind: Option<Ident>,
+    scope: Option<Ident>,
+    level: Option<Expr>,
+    retry: Option<Ident>,
+    pass_through: Option<Ident>,
+    mapping_code: Option<Expr>,
+    message: Option<LitStr>,
+    i18n: Option<LitStr>,
+    args: Vec<Ident>,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute], mut base: Attrs) -> Result<Attrs> {
+        for attr in attrs.iter().filter(|a| a.path().is_ident("wid")) {
+            attr.parse_nested_meta(|meta| {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map(Ident::to_string)
+                    .unwrap_or_default();
+                match key.as_str() {
+                    "code" => base.code = Some(meta.value()?.parse()?),
+                    "name" => base.name = Some(meta.value()?.parse()?),
+                    "namespace" => base.namespace = Some(meta.value()?.parse()?),
+                    "kind" => base.kind = Some(meta.value()?.parse()?),
+                    "scope" => base.scope = Some(meta.value()?.parse()?),
+                    "level" => base.level = Some(meta.value()?.parse()?),
+                    "retry" => base.retry = Some(meta.value()?.parse()?),
+                    "pass_through" => base.pass_through = Some(meta.value()?.parse()?),
+                    "mapping_code" => base.mapping_code = Some(meta.value()?.parse()?),
+                    "message" => base.message = Some(meta.value()?.parse()?),
+                    "i18n" => base.i18n = Some(meta.value()?.parse()?),
+                    "args" => meta.parse_nested_meta(|arg| {
+                        base.args.push(arg.path.require_ident()?.clone());
+                        Ok(())
+                    })?,
+                    _ => return Err(meta.error("unknown wid attribute")),
+                }
+                Ok(())
+            })?;
+        }
+        Ok(base)
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "WidError can only be derived for enums",
+            ))
+        }
+    };
+    let defaults = Attrs::parse(&input.attrs, Attrs::default())?;
+    if defaults.code.is_some()
+        || defaults.name.is_some()
+        || defaults.message.is_some()
+        || defaults.i18n.is_some()
+        || !defaults.args.is_empty()
+    {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`code`, `name`, `message`, `i18n` and `args` must be set per variant",
+        ));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut display_arms = Vec::new();
+    let mut from_arms = Vec::new();
+    for variant in &data.variants {
+        let attrs = Attrs::parse(&variant.attrs, defaults.clone())?;
+        let pattern = pattern(ident, variant);
+        let text = attrs
+            .message
+            .as_ref()
+            .map(|m| LitStr::new(&positional_to_named(&m.value()), m.span()));
+        let text =
+            text.unwrap_or_else(|| LitStr::new(&variant.ident.to_string(), variant.ident.span()));
+        display_arms.push(quote! { #pattern => ::core::write!(f, #text), });
+        from_arms.push(conversion(ident, variant, &attrs)?);
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    #(#display_arms)*
+                }
+            }
+        }
+
+        impl #impl_generics ::std::error::Error for #ident #ty_generics #where_clause {}
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for ::widerror::WidError #where_clause {
+            #[allow(unused_variables)]
+            #[track_caller]
+            fn from(e: #ident #ty_generics) -> Self {
+                let text = ::std::string::ToString::to_string(&e);
+                match &e {
+                    #(#from_arms)*
+                }
+            }
+        }
+    })
+}
+
+/// Builds the match arm that turns one variant into a `WidError`.
+fn conversion(ident: &Ident, variant: &Variant, attrs: &Attrs) -> Result<TokenStream2> {
+    let v = &variant.ident;
+    let code = attrs
+        .code
+        .as_ref()
+        .ok_or_else(|| Error::new_spanned(v, "missing `#[wid(code = ...)]`"))?;
+    // Evaluated in a `const` so invalid digits or a namespace mismatch fail to compile.
+    let code = match &attrs.namespace {
+        Some(ns) => quote! {
+            ::widerror::ErrorCode::with_namespace(#code, ::widerror::Namespace::new(#ns))
+        },
+        None => quote! { ::widerror::ErrorCode::new(#code) },
+    };
+    let name = attrs
+        .name
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&screaming_snake(&v.to_string()), v.span()));
+    let message = match &attrs.i18n {
+        Some(key) => quote! { ::widerror::Message::I18n(::widerror::Template::from(#key)) },
+        None => quote! { ::widerror::Message::Default(::widerror::Template::from(text)) },
+    };
+    let pattern = pattern(ident, variant);
+    let args = attrs.args.iter().map(|arg| {
+        let key = arg.to_string();
+        quote! { .arg(#key, ::core::clone::Clone::clone(#arg)) }
+    });
+    let message = quote! { #message #(#args)* };
+    let mut sets = vec![quote! { err.name = ::std::string::String::from(#name); }];
+    if let Some(kind) = &attrs.kind {
+        sets.push(quote! { err.kind = ::widerror::Kind::#kind; });
+    }
+    if let Some(scope) = &attrs.scope {
+        sets.push(quote! { err.scope = ::widerror::Scope::#scope; });
+    }
+    if let Some(level) = &attrs.level {
+        sets.push(quote! { err.level = #level; });
+    }
+    if let Some(retry) = &attrs.retry {
+        sets.push(quote! { err.retry_mode = ::widerror::RetryMode::#retry; });
+    }
+    if let Some(pass_through) = &attrs.pass_through {
+        sets.push(quote! { err.pass_through_mode = ::widerror::PassThroughMode::#pass_through; });
+    }
+    if let Some(mapping_code) = &attrs.mapping_code {
+        sets.push(quote! { err.mapping_code = #mapping_code; });
+    }
+    Ok(quote! {
+        #pattern => {
+            const CODE: ::widerror::ErrorCode = #code;
+            let mut err = ::widerror::WidError::new(CODE, #message);
+            #(#sets)*
+            err
+        }
+    })
+}
+
+/// Binds every field so the message can refer to it by name (`{id}`) or
+/// position (`{0}`, bound as `_0`).
+fn pattern(ident: &Ident, variant: &Variant) -> TokenStream2 {
+    let v = &variant.ident;
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
+            quote! { #ident::#v { #(#names),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let names = (0..fields.unnamed.len()).map(|i| format_ident!("_{}", i));
+            quote! { #ident::#v ( #(#names),* ) }
+        }
+        Fields::Unit => quote! { #ident::#v },
+    }
+}
+
+/// Rewrites `{0}` / `{0:?}` into `{_0}` / `{_0:?}` so tuple fields can be
+/// captured as inline format arguments.
+fn positional_to_named(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            out.push(chars.next().unwrap());
+            continue;
+        }
+        if chars.peek().is_some_and(char::is_ascii_digit) {
+            out.push('_');
+        }
+    }
+    out
+}
+
+fn screaming_snake(ident: &str) -> String {
+    let mut out = String::with_capacity(ident.len() + 4);
+    for (i, c) in ident.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.push(c.to_ascii_uppercase());
+    }
+    out
+}
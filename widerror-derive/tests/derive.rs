@@ -0,0 +1,63 @@
+use widerror::{Arg, Kind, Message, PassThroughMode, RetryMode, Scope, WidError};
+
+#[derive(Debug, WidError)]
+#[wid(namespace = 10001, scope = Clientside)]
+enum OrderError {
+    #[wid(code = 100010001, kind = NotFound, level = 3, retry = Denied, message = "order {id} not found")]
+    NotFound { id: u64 },
+    #[wid(code = 100010002, kind = InvalidArgument, message = "bad quantity {0} for {1:?}")]
+    BadQuantity(i32, String),
+    #[wid(code = 100010003, name = "ORDER_SERVICE_BUSY", kind = Unavailable, scope = Serverside, retry = Allowed, pass_through = Should, mapping_code = -1)]
+    Busy,
+    #[wid(code = 100010004, i18n = "order.locked", args(id, by))]
+    Locked { id: u64, by: String },
+}
+
+#[test]
+fn display_interpolates_fields() {
+    assert_eq!(
+        OrderError::NotFound { id: 42 }.to_string(),
+        "order 42 not found"
+    );
+    assert_eq!(
+        OrderError::BadQuantity(-1, "apple".into()).to_string(),
+        "bad quantity -1 for \"apple\""
+    );
+    assert_eq!(OrderError::Busy.to_string(), "Busy");
+}
+
+#[test]
+fn converts_into_wid_error() {
+    let err: WidError = OrderError::NotFound { id: 7 }.into();
+    assert_eq!(err.code, 100010001);
+    assert_eq!(err.name, "NOT_FOUND");
+    assert_eq!(err.namespace, 10001);
+    assert_eq!(err.kind, Kind::NotFound);
+    assert_eq!(err.scope, Scope::Clientside);
+    assert_eq!(err.level, 3);
+    assert_eq!(err.retry_mode, RetryMode::Denied);
+    assert!(matches!(err.message, Message::Default(ref m) if m.text == "order 7 not found"));
+
+    let err = WidError::from(OrderError::Busy);
+    assert_eq!(err.name, "ORDER_SERVICE_BUSY");
+    assert_eq!(err.scope, Scope::Serverside);
+    assert_eq!(err.pass_through_mode, PassThroughMode::Should);
+    assert_eq!(err.mapping_code, -1);
+
+    let err = WidError::from(OrderError::Locked {
+        id: 9,
+        by: "alice".into(),
+    });
+    assert!(matches!(err.message, Message::I18n(ref k) if k.text == "order.locked"));
+    assert_eq!(err.message.args().get("id"), Some(&Arg::Int(9)));
+    assert_eq!(
+        err.message.args().get("by"),
+        Some(&Arg::Str("alice".into()))
+    );
+}
+
+#[test]
+fn implements_std_error() {
+    let err: Box<dyn std::error::Error> = Box::new(OrderError::Busy);
+    assert!(err.source().is_none());
+}